@@ -0,0 +1,136 @@
+use crate::luau_sandbox::{Island, create_lua_sandbox_and_island, exec_island_script};
+use crate::mechanics::{IslandData, RoomId};
+use godot::classes::ProjectSettings;
+use godot::prelude::*;
+use std::path::PathBuf;
+
+/// Read-only view of a loaded island's topology for GDScript,
+/// so the game can query rooms without going through the Luau binding
+#[derive(GodotClass)]
+#[class(init, base=RefCounted)]
+pub struct IslandRuntime {
+    data: Option<IslandData>,
+    base: Base<RefCounted>,
+}
+
+impl IslandRuntime {
+    pub fn from_island_data(data: IslandData) -> Gd<Self> {
+        Gd::from_init_fn(|base| Self {
+            data: Some(data),
+            base,
+        })
+    }
+
+    /// Snapshot a scripted island; `None` until its island config has been loaded
+    pub fn from_island(island: &Island) -> Option<Gd<Self>> {
+        island
+            .get_mechanics_island_data()
+            .map(|data| Self::from_island_data(data.as_ref().clone()))
+    }
+}
+
+#[godot_api]
+impl IslandRuntime {
+    #[func]
+    fn rooms_are_adjacent(&self, room_a: i64, room_b: i64) -> bool {
+        match (&self.data, room_id(room_a), room_id(room_b)) {
+            (Some(data), Some(a), Some(b)) => data.rooms_are_adjacent(a, b),
+            _ => false,
+        }
+    }
+
+    #[func]
+    fn get_room_count(&self) -> i64 {
+        self.data
+            .as_ref()
+            .map_or(0, |data| data.rooms().len() as i64)
+    }
+
+    /// Room ids from `from` to `to` inclusive, or empty when there is no route
+    #[func]
+    fn find_path(&self, from: i64, to: i64) -> PackedInt32Array {
+        let path = match (&self.data, room_id(from), room_id(to)) {
+            (Some(data), Some(from), Some(to)) => data.find_path(from, to).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        path.into_iter().map(|id| id as i32).collect()
+    }
+}
+
+/// Runs an `island.luau` in a fresh sandbox and keeps the result; the editor plugin's entry point
+#[derive(GodotClass)]
+#[class(init, tool, base=Node)]
+pub struct IslandLoader {
+    island: Option<Island>,
+    last_error: GString,
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl IslandLoader {
+    /// Both paths may be `res://` paths. Returns false and records `get_last_error` on failure,
+    /// leaving any previously loaded island in place.
+    #[func]
+    fn load_island(&mut self, script_path: GString, base_path: GString) -> bool {
+        let project_settings = ProjectSettings::singleton();
+        let script_path = PathBuf::from(project_settings.globalize_path(&script_path).to_string());
+        let base_path = PathBuf::from(project_settings.globalize_path(&base_path).to_string());
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.set_base_path(base_path);
+        // Loaded callbacks run once the whole script has registered everything
+        if let Err(e) = exec_island_script(&lua, &script_path).and_then(|()| island.finalize()) {
+            let message = format!("Failed to load island {}: {}", script_path.display(), e);
+            godot_error!("{}", message);
+            self.last_error = message.into();
+            return false;
+        }
+
+        self.island = Some(island);
+        self.last_error = GString::new();
+        true
+    }
+
+    #[func]
+    fn is_loaded(&self) -> bool {
+        self.island.is_some()
+    }
+
+    #[func]
+    fn get_last_error(&self) -> GString {
+        self.last_error.clone()
+    }
+
+    #[func]
+    fn get_tile_layers(&self) -> PackedStringArray {
+        self.island
+            .as_ref()
+            .map(|island| to_packed_strings(island.get_tile_layers()))
+            .unwrap_or_default()
+    }
+
+    #[func]
+    fn get_entity_layers(&self) -> PackedStringArray {
+        self.island
+            .as_ref()
+            .map(|island| to_packed_strings(island.get_entity_layers()))
+            .unwrap_or_default()
+    }
+
+    /// Topology snapshot of the loaded island, or null if the script never loaded an island config
+    #[func]
+    fn get_island_runtime(&self) -> Option<Gd<IslandRuntime>> {
+        self.island.as_ref().and_then(IslandRuntime::from_island)
+    }
+}
+
+fn to_packed_strings(values: Vec<String>) -> PackedStringArray {
+    values
+        .iter()
+        .map(|value| GString::from(value.as_str()))
+        .collect()
+}
+
+fn room_id(id: i64) -> Option<RoomId> {
+    RoomId::try_from(id).ok()
+}
@@ -2,10 +2,12 @@ use crate::networking::TokioRuntime;
 use godot::classes::Engine;
 use godot::prelude::*;
 
+mod island_runtime;
 mod local;
 mod luau_sandbox;
 mod mechanics;
 mod networking;
+mod protocol;
 
 struct RustExtension;
 
@@ -31,6 +33,8 @@ unsafe impl ExtensionLibrary for RustExtension {
                 // Here is where we free our async runtime singleton from memory.
                 if let Some(async_singleton) = engine.get_singleton(TokioRuntime::SINGLETON) {
                     engine.unregister_singleton(TokioRuntime::SINGLETON);
+                    let mut async_singleton = async_singleton.cast::<TokioRuntime>();
+                    async_singleton.bind_mut().shutdown();
                     async_singleton.free();
                 } else {
                     godot_warn!("Failed to free singleton -> {}", TokioRuntime::SINGLETON);
@@ -1,61 +1,762 @@
 use crate::mechanics::{
-    EntitySpawn, Island as MechanicsIsland, IslandData as MechanicsIslandData, Room,
+    AdjacencyReason, Edge, EntitySpawn, Island as MechanicsIsland,
+    IslandData as MechanicsIslandData, IslandPatch, Orientation, PaletteIndex, PathStep, Room,
+    RoomBounds, SCHEMA_VERSION, TileData, WorldWrap, check_schema_version,
+};
+use ghx_grid::grid::GridIndex;
+use mlua::{
+    Error as LuaError, FromLua, Function, IntoLua, Lua, Table, UserData, Value, Variadic, VmState,
 };
-use mlua::{Error as LuaError, Function, Lua, Table, UserData, Value};
 use path_security::{validate_filename, validate_path};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+/// Execution limits applied to a sandboxed island script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SandboxLimits {
+    /// Luau interrupt checks (function calls and loop iterations) allowed before aborting
+    pub max_instructions: u64,
+    /// Bytes the Lua heap may grow to before allocations fail with a memory error
+    pub max_memory: usize,
+    /// Whether loaders may read files under the content root. Without it scripts must build
+    /// their island in memory, e.g. with `register_room_table`
+    pub filesystem_access: bool,
+}
+
+impl SandboxLimits {
+    /// Limits used by the vanilla loader and other first-party content
+    pub const TRUSTED: SandboxLimits = SandboxLimits {
+        max_instructions: 50_000_000,
+        max_memory: 256 * 1024 * 1024,
+        filesystem_access: true,
+    };
+
+    /// Tighter limits for community content
+    pub const UNTRUSTED: SandboxLimits = SandboxLimits {
+        max_instructions: 1_000_000,
+        max_memory: 32 * 1024 * 1024,
+        filesystem_access: false,
+    };
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self::TRUSTED
+    }
+}
+
+/// Why loading island data failed. Scripts see it as a Lua error whose message starts with a
+/// fixed tag per variant, e.g. `[io]`; Rust callers can downcast the `LuaError` and match.
+#[derive(Debug)]
+pub enum IslandError {
+    /// A file or directory couldn't be read or written
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// A RON file didn't match the expected shape
+    RonParse {
+        kind: &'static str,
+        path: String,
+        source: ron::error::SpannedError,
+    },
+    /// A pre-baked binary island was malformed or baked for another schema version
+    BinaryParse { path: String, message: String },
+    /// Some files in a directory failed to read or parse, one `file:line:col: reason` each
+    DirectoryParse {
+        kind: &'static str,
+        dir: String,
+        failures: Vec<String>,
+    },
+    /// A script-supplied path or name was rejected by the sandbox
+    PathSecurity {
+        path: String,
+        rejection: PathRejection,
+    },
+    /// The data parsed but breaks a rule, e.g. overlapping rooms or a newer schema
+    Validation(String),
+}
+
+impl IslandError {
+    /// The `IslandError` behind a Lua error, looking through the callback that raised it
+    pub fn from_lua_error(error: &LuaError) -> Option<&IslandError> {
+        match error {
+            LuaError::CallbackError { cause, .. } => Self::from_lua_error(cause),
+            LuaError::WithContext { cause, .. } => Self::from_lua_error(cause),
+            LuaError::ExternalError(error) => error.downcast_ref::<IslandError>(),
+            _ => None,
+        }
+    }
+
+    /// `map_err` adapter for IO failures, `context` saying what was being attempted
+    fn io(context: String) -> impl FnOnce(std::io::Error) -> IslandError {
+        move |source| IslandError::Io { context, source }
+    }
+
+    fn path(path: &str, rejection: PathRejection) -> IslandError {
+        IslandError::PathSecurity {
+            path: path.to_string(),
+            rejection,
+        }
+    }
+
+    /// `map_err` adapter for RON failures in `path`, a file holding a `kind`
+    fn ron_parse(
+        kind: &'static str,
+        path: &str,
+    ) -> impl FnOnce(ron::error::SpannedError) -> IslandError {
+        let path = path.to_string();
+        move |source| IslandError::RonParse { kind, path, source }
+    }
+
+    /// `path:line:col: reason`, so editors can jump straight to the bad token
+    fn ron_location(path: &str, source: &ron::error::SpannedError) -> String {
+        format!(
+            "{}:{}:{}: {}",
+            path, source.position.line, source.position.col, source.code
+        )
+    }
+}
+
+impl std::fmt::Display for IslandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IslandError::Io { context, source } => write!(f, "[io] {}: {}", context, source),
+            IslandError::RonParse { kind, path, source } => write!(
+                f,
+                "[parse] Failed to parse {} {}",
+                kind,
+                Self::ron_location(path, source)
+            ),
+            IslandError::BinaryParse { path, message } => {
+                write!(
+                    f,
+                    "[parse] Failed to parse binary island {}: {}",
+                    path, message
+                )
+            }
+            IslandError::DirectoryParse {
+                kind,
+                dir,
+                failures,
+            } => write!(
+                f,
+                "[parse] Failed to parse {} files in {}:\n{}",
+                kind,
+                dir,
+                failures.join("\n")
+            ),
+            IslandError::PathSecurity { path, rejection } => match rejection {
+                PathRejection::EscapesRoot => {
+                    write!(f, "[path] {} escapes the content root", path)
+                }
+                PathRejection::NotFound => {
+                    write!(f, "[path] {} not found under the content root", path)
+                }
+                PathRejection::Invalid(reason) => write!(f, "[path] {}: {}", path, reason),
+                PathRejection::FilesystemDisabled => {
+                    write!(
+                        f,
+                        "[path] {}: filesystem access disabled in this sandbox",
+                        path
+                    )
+                }
+            },
+            IslandError::Validation(message) => write!(f, "[validation] {}", message),
+        }
+    }
+}
+
+impl std::error::Error for IslandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IslandError::Io { source, .. } => Some(source),
+            IslandError::RonParse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<IslandError> for LuaError {
+    fn from(error: IslandError) -> Self {
+        LuaError::external(error)
+    }
+}
+
+/// Why a script-supplied path was refused, so a mistyped name reads differently from an
+/// attempt to leave the sandbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathRejection {
+    /// `..` climbs above the content root, or the path is absolute
+    EscapesRoot,
+    /// The path stays inside the root but nothing exists there
+    NotFound,
+    /// Refused by `path_security` for another reason, e.g. a reserved name
+    Invalid(String),
+    /// The sandbox was created without filesystem access, so no path is accepted
+    FilesystemDisabled,
+}
+
+/// Resolve a script-supplied path inside the sandbox root
+fn resolve_path(path: &str, base_path: &Path) -> Result<PathBuf, IslandError> {
+    if escapes_root(Path::new(path)) {
+        return Err(IslandError::path(path, PathRejection::EscapesRoot));
+    }
+    validate_path(Path::new(path), base_path)
+        .map_err(|e| IslandError::path(path, PathRejection::Invalid(e.to_string())))
+}
+
+/// `resolve_path` for something about to be read, which must already exist
+fn resolve_existing_path(path: &str, base_path: &Path) -> Result<PathBuf, IslandError> {
+    let full_path = resolve_path(path, base_path)?;
+    if !full_path.exists() {
+        return Err(IslandError::path(path, PathRejection::NotFound));
+    }
+    Ok(full_path)
+}
+
+/// Whether `path` is absolute or its `..` components climb above where it starts
+fn escapes_root(path: &Path) -> bool {
+    let mut depth: usize = 0;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => return true,
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => return true,
+            },
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+        }
+    }
+    false
+}
+
+/// Serialized untagged for JSON export; the field's declared type tells the variants apart
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum DefaultValue {
     Int(i64),
     Float(f64),
     String(String),
     Bool(bool),
+    List(Vec<DefaultValue>),
+    Map(HashMap<String, DefaultValue>),
+    /// RGBA channels parsed from `#RRGGBB` or `#RRGGBBAA`
+    Color([u8; 4]),
+    /// x, y, z components of a position or direction
+    Vec3(f64, f64, f64),
 }
 
-#[derive(Debug, Clone)]
+/// Type names accepted for `value_type`, `item_type`, `keys` and `schema` entries
+pub const FIELD_TYPES: &[&str] = &[
+    "int", "float", "string", "bool", "enum", "color", "vector3", "list", "map",
+];
+
+/// Key types a `map` field may declare with `keys`
+pub const MAP_KEY_TYPES: &[&str] = &["string", "int"];
+
+/// File extensions `register_gltf` accepts for model paths, compared case-insensitively
+pub const GLTF_EXTENSIONS: &[&str] = &["gltf", "glb"];
+
+#[derive(Debug, Clone, Serialize)]
 pub struct FieldOptions {
     pub default: Option<DefaultValue>,
     pub min: Option<i64>,
     pub max: Option<i64>,
+    /// Enum labels, ordered by their integer value when given as a label -> value map
     pub values: Option<Vec<String>>,
+    /// Label and stored integer per enum value, ascending by value; only for the map form
+    pub enum_values: Option<Vec<(String, i64)>>,
     pub keys: Option<String>,
     pub value_type: Option<String>,
     pub item_type: Option<String>,
     pub schema: Option<HashMap<String, String>>,
+    /// Every spawn of the owning entity type must set this field explicitly
+    pub required: bool,
+    /// Tooltip text for the editor property panel
+    pub description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+/// Exported with the same flat shape scripts see from `get_tile_fields`
+#[derive(Debug, Clone, Serialize)]
 pub struct FieldRegistration {
+    #[serde(rename = "name")]
     pub field_name: String,
+    #[serde(rename = "type")]
     pub field_type: String,
+    #[serde(flatten)]
     pub options: FieldOptions,
 }
 
+impl IntoLua for DefaultValue {
+    fn into_lua(self, lua: &Lua) -> mlua::Result<Value> {
+        match self {
+            DefaultValue::Int(i) => Ok(Value::Integer(i)),
+            DefaultValue::Float(n) => Ok(Value::Number(n)),
+            DefaultValue::String(s) => s.into_lua(lua),
+            DefaultValue::Bool(b) => Ok(Value::Boolean(b)),
+            DefaultValue::List(items) => items.into_lua(lua),
+            DefaultValue::Map(entries) => entries.into_lua(lua),
+            DefaultValue::Color([r, g, b, a]) => {
+                let color = lua.create_table()?;
+                color.set("r", r)?;
+                color.set("g", g)?;
+                color.set("b", b)?;
+                color.set("a", a)?;
+                Ok(Value::Table(color))
+            }
+            DefaultValue::Vec3(x, y, z) => {
+                let vector = lua.create_table()?;
+                vector.set("x", x)?;
+                vector.set("y", y)?;
+                vector.set("z", z)?;
+                Ok(Value::Table(vector))
+            }
+        }
+    }
+}
+
+/// Field metadata as seen by scripts and the editor property panel
+impl IntoLua for FieldRegistration {
+    fn into_lua(self, lua: &Lua) -> mlua::Result<Value> {
+        let field = lua.create_table()?;
+        field.set("name", self.field_name)?;
+        field.set("type", self.field_type)?;
+        field.set("default", self.options.default)?;
+        field.set("min", self.options.min)?;
+        field.set("max", self.options.max)?;
+        field.set("values", self.options.values)?;
+        let enum_values = match self.options.enum_values {
+            Some(enum_values) => {
+                let entries = lua.create_table()?;
+                for (label, value) in enum_values {
+                    let entry = lua.create_table()?;
+                    entry.set("label", label)?;
+                    entry.set("value", value)?;
+                    entries.push(entry)?;
+                }
+                Some(entries)
+            }
+            None => None,
+        };
+        field.set("enum_values", enum_values)?;
+        field.set("keys", self.options.keys)?;
+        field.set("value_type", self.options.value_type)?;
+        field.set("item_type", self.options.item_type)?;
+        field.set("schema", self.options.schema)?;
+        field.set("required", self.options.required)?;
+        field.set("description", self.options.description)?;
+        Ok(Value::Table(field))
+    }
+}
+
+/// Stage of loading an island that `LoadTimings` accounts time to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPhase {
+    Config,
+    Rooms,
+    Spawns,
+    Validation,
+}
+
+impl LoadPhase {
+    pub const ALL: [LoadPhase; 4] = [
+        LoadPhase::Config,
+        LoadPhase::Rooms,
+        LoadPhase::Spawns,
+        LoadPhase::Validation,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LoadPhase::Config => "config",
+            LoadPhase::Rooms => "rooms",
+            LoadPhase::Spawns => "spawns",
+            LoadPhase::Validation => "validation",
+        }
+    }
+}
+
+/// Time spent in each load phase, summed over every loader call since the island was cleared
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoadTimings {
+    pub config: Duration,
+    pub rooms: Duration,
+    pub spawns: Duration,
+    pub validation: Duration,
+}
+
+impl LoadTimings {
+    pub fn get(&self, phase: LoadPhase) -> Duration {
+        match phase {
+            LoadPhase::Config => self.config,
+            LoadPhase::Rooms => self.rooms,
+            LoadPhase::Spawns => self.spawns,
+            LoadPhase::Validation => self.validation,
+        }
+    }
+
+    fn get_mut(&mut self, phase: LoadPhase) -> &mut Duration {
+        match phase {
+            LoadPhase::Config => &mut self.config,
+            LoadPhase::Rooms => &mut self.rooms,
+            LoadPhase::Spawns => &mut self.spawns,
+            LoadPhase::Validation => &mut self.validation,
+        }
+    }
+}
+
+/// Adds the time until it's dropped to one load phase, so loaders are timed on every exit path
+struct LoadTimer<'a> {
+    island: &'a Island,
+    phase: LoadPhase,
+    source: String,
+    started: Instant,
+}
+
+impl Drop for LoadTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        *self
+            .island
+            .data
+            .lock()
+            .unwrap()
+            .load_timings
+            .get_mut(self.phase) += elapsed;
+        log::debug!(
+            "[load] {} {} took {:.3} ms",
+            self.phase.name(),
+            self.source,
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct IslandData {
+    /// Layers in draw order, kept exactly as scripts set, add or move them
     pub tile_layers: Vec<String>,
     pub entity_layers: Vec<String>,
     pub tile_fields: HashMap<String, Vec<FieldRegistration>>,
     pub entity_fields: HashMap<String, Vec<FieldRegistration>>,
-    // Runtime loaded data
-    pub island_config: Option<MechanicsIsland>,
-    pub rooms: Vec<Room>,
-    pub entity_spawns: Vec<EntitySpawn>,
+    // Runtime loaded data. The config, rooms, spawns, world wrap and tile costs are mirrored
+    // by `Island::get_mechanics_island_data`, so every write to them drops `mechanics_cache`.
+    island_config: Option<MechanicsIsland>,
+    rooms: Vec<Room>,
+    entity_spawns: Vec<EntitySpawn>,
     pub gltf_registry: HashMap<String, PathBuf>,
+    /// Name of the registered GLTF model drawn for each tile palette index
+    pub tile_palette: HashMap<PaletteIndex, String>,
+    /// Movement cost per palette, saved with the island, see `MechanicsIslandData::tile_costs`
+    tile_costs: HashMap<PaletteIndex, f64>,
+    /// Axes along which the world wraps, applied to adjacency queries
+    world_wrap: WorldWrap,
+    /// Built on the first topology query and shared until the data above changes, so
+    /// queries don't re-clone every room and rebuild the spatial index each call
+    mechanics_cache: Option<Arc<MechanicsIslandData>>,
     pub base_path: PathBuf,
+    /// Off for untrusted content, see `SandboxLimits::filesystem_access`
+    pub filesystem_access: bool,
     pub room_process_fns: HashMap<u32, mlua::RegistryKey>,
     pub room_physics_process_fns: HashMap<u32, mlua::RegistryKey>,
     // Process callbacks (cannot be cloned due to RegistryKey)
     pub process_fn: Option<mlua::RegistryKey>,
     pub physics_process_fn: Option<mlua::RegistryKey>,
+    /// Run once by `Island::finalize` after the island script, in registration order
+    pub loaded_fns: Vec<mlua::RegistryKey>,
+    /// Time spent loading and validating so far, see `island:get_load_timings()`
+    pub load_timings: LoadTimings,
+    // Seeded randomness for procedural generation
+    pub rng: IslandRng,
+}
+
+/// xorshift64* generator giving island scripts reproducible randomness.
+/// Independent of Luau's own math.random, which scripts should not rely on for generation.
+#[derive(Debug, Clone)]
+pub struct IslandRng {
+    state: u64,
+}
+
+impl IslandRng {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 scramble so small or zero seeds still start from a well-mixed non-zero state
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        IslandRng {
+            state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform float in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in [min, max], both inclusive
+    pub fn range_i64(&mut self, min: i64, max: i64) -> i64 {
+        let span = (max as i128 - min as i128 + 1) as u128;
+        (min as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+}
+
+impl Default for IslandRng {
+    fn default() -> Self {
+        IslandRng::new(0)
+    }
+}
+
+impl IslandData {
+    /// Tiles placed across every registered room
+    pub fn tile_count(&self) -> usize {
+        self.rooms.iter().map(|room| room.tiles.len()).sum()
+    }
+
+    /// Rooms for editing in place; topology queries see the change on their next call
+    fn rooms_mut(&mut self) -> &mut Vec<Room> {
+        self.invalidate_mechanics_cache();
+        &mut self.rooms
+    }
+
+    fn invalidate_mechanics_cache(&mut self) {
+        self.mechanics_cache = None;
+    }
+
+    /// Drop everything scripts registered, keeping only the content root
+    fn clear(&mut self) {
+        *self = IslandData {
+            base_path: std::mem::take(&mut self.base_path),
+            filesystem_access: self.filesystem_access,
+            ..Default::default()
+        };
+    }
+
+    /// `resolve_path` against this island's root, refusing every path when the sandbox has
+    /// no filesystem access
+    fn resolve_path(&self, path: &str) -> Result<PathBuf, IslandError> {
+        self.check_filesystem_access(path)?;
+        resolve_path(path, &self.base_path)
+    }
+
+    /// `resolve_existing_path` against this island's root, under the same rule
+    fn resolve_existing_path(&self, path: &str) -> Result<PathBuf, IslandError> {
+        self.check_filesystem_access(path)?;
+        resolve_existing_path(path, &self.base_path)
+    }
+
+    fn check_filesystem_access(&self, path: &str) -> Result<(), IslandError> {
+        if self.filesystem_access {
+            Ok(())
+        } else {
+            Err(IslandError::path(path, PathRejection::FilesystemDisabled))
+        }
+    }
+
+    /// Read, parse and check one room file, resolved against the sandbox root
+    fn read_room(&self, path: &str) -> Result<Room, IslandError> {
+        let full_path = self.resolve_existing_path(path)?;
+        let content = std::fs::read_to_string(&full_path).map_err(IslandError::io(format!(
+            "Failed to read room file {}",
+            path
+        )))?;
+        let room: Room =
+            ron::from_str(&content).map_err(IslandError::ron_parse("room file", path))?;
+        check_schema_version(room.version)
+            .and_then(|()| room.check_tile_bounds())
+            .map_err(|e| IslandError::Validation(format!("Room file {}: {}", path, e)))?;
+        Ok(room.migrate())
+    }
+
+    /// Parse every `.ron` file in `dir`, a directory of `kind`s, in file name order, vetting
+    /// each value with `check`. Everything is parsed before failing so one bad file doesn't
+    /// hide the others.
+    fn read_ron_dir<T: DeserializeOwned>(
+        &self,
+        dir: &str,
+        kind: &'static str,
+        check: impl Fn(T) -> Result<T, String>,
+    ) -> Result<Vec<T>, IslandError> {
+        let full_dir = self.resolve_existing_path(dir)?;
+        let entries = std::fs::read_dir(&full_dir).map_err(IslandError::io(format!(
+            "Failed to read {} directory {}",
+            kind, dir
+        )))?;
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("ron"))
+            .collect();
+        paths.sort();
+
+        let mut values = Vec::new();
+        let mut failures = Vec::new();
+        for path in &paths {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            match std::fs::read_to_string(path) {
+                Ok(content) => match ron::from_str::<T>(&content) {
+                    Ok(value) => match check(value) {
+                        Ok(value) => values.push(value),
+                        Err(e) => failures.push(format!("{}: {}", file_name, e)),
+                    },
+                    Err(e) => failures.push(IslandError::ron_location(&file_name, &e)),
+                },
+                Err(e) => failures.push(format!("{}: {}", file_name, e)),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(IslandError::DirectoryParse {
+                kind,
+                dir: dir.to_string(),
+                failures,
+            });
+        }
+        Ok(values)
+    }
+
+    /// Put `room` where the registered room with the same id sits, checking it still
+    /// doesn't overlap any of the others
+    fn replace_room(&mut self, room: Room) -> Result<(), IslandError> {
+        let Some(position) = self.rooms.iter().position(|r| r.room_id == room.room_id) else {
+            return Err(IslandError::Validation(format!(
+                "Room {} is not registered",
+                room.room_id
+            )));
+        };
+        if let Some(existing) = self
+            .rooms
+            .iter()
+            .find(|r| r.room_id != room.room_id && Room::overlaps(r, &room))
+        {
+            return Err(IslandError::Validation(format!(
+                "Room {} overlaps already registered room {}",
+                room.room_id, existing.room_id
+            )));
+        }
+        self.rooms_mut()[position] = room;
+        Ok(())
+    }
+
+    /// Add a parsed room after checking it against the rooms already registered
+    fn insert_room(&mut self, room: Room) -> Result<(), IslandError> {
+        if let Some(existing) = self.rooms.iter().find(|r| Room::overlaps(r, &room)) {
+            return Err(IslandError::Validation(format!(
+                "Room {} overlaps already registered room {}",
+                room.room_id, existing.room_id
+            )));
+        }
+        self.rooms_mut().push(room);
+        Ok(())
+    }
+
+    /// Add several parsed rooms, or none of them if any overlaps a registered room or
+    /// another room in the batch
+    fn insert_rooms(&mut self, rooms: Vec<Room>) -> Result<(), IslandError> {
+        for (index, room) in rooms.iter().enumerate() {
+            if let Some(existing) = self.rooms.iter().find(|r| Room::overlaps(r, room)) {
+                return Err(IslandError::Validation(format!(
+                    "Room {} overlaps already registered room {}",
+                    room.room_id, existing.room_id
+                )));
+            }
+            if let Some(earlier) = rooms[..index].iter().find(|r| Room::overlaps(r, room)) {
+                return Err(IslandError::Validation(format!(
+                    "Room {} overlaps room {} loaded with it",
+                    room.room_id, earlier.room_id
+                )));
+            }
+        }
+        self.rooms_mut().extend(rooms);
+        Ok(())
+    }
+
+    /// Check entity spawns against the fields registered for their entity type
+    fn spawn_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut spawns_by_id: HashMap<&str, &EntitySpawn> = HashMap::new();
+        for spawn in &self.entity_spawns {
+            let Some(id) = spawn.id.as_deref() else {
+                continue;
+            };
+            if let Some(first) = spawns_by_id.get(id) {
+                problems.push(format!(
+                    "{} reuses id {:?} from {}",
+                    spawn_location(spawn),
+                    id,
+                    spawn_location(first)
+                ));
+            } else {
+                spawns_by_id.insert(id, spawn);
+            }
+        }
+
+        for spawn in &self.entity_spawns {
+            let location = spawn_location(spawn);
+            let fields = self
+                .entity_fields
+                .get(&spawn.entity_type)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            for field in fields.iter().filter(|f| f.options.required) {
+                if !spawn.properties.contains_key(&field.field_name) {
+                    problems.push(format!(
+                        "{} is missing required field {}",
+                        location, field.field_name
+                    ));
+                }
+            }
+
+            let mut keys: Vec<&String> = spawn.properties.keys().collect();
+            keys.sort();
+            for key in keys {
+                match fields.iter().find(|f| &f.field_name == key) {
+                    Some(field) => {
+                        let raw = &spawn.properties[key];
+                        if let Err(reason) = check_property_value(field, raw) {
+                            problems.push(format!("{} property {}: {}", location, key, reason));
+                        } else if field.field_type == "entity_ref"
+                            && !spawns_by_id.contains_key(raw.trim())
+                        {
+                            problems.push(format!(
+                                "{} property {} points at missing spawn id {:?}",
+                                location,
+                                key,
+                                raw.trim()
+                            ));
+                        }
+                    }
+                    None => problems.push(format!("{} has unknown property {}", location, key)),
+                }
+            }
+        }
+        problems
+    }
 }
 
 #[derive(Clone)]
 pub struct Island {
     data: Arc<Mutex<IslandData>>,
+    instructions_used: Arc<AtomicU64>,
+    /// The Lua whose registry holds this island's callbacks. Only the handle returned to Rust
+    /// carries it; the copies inside Lua leave it empty so the state doesn't keep itself alive.
+    lua: Option<Arc<Lua>>,
 }
 
 impl Island {
@@ -63,24 +764,161 @@ impl Island {
         Island {
             data: Arc::new(Mutex::new(IslandData {
                 base_path: PathBuf::from("tbol_vanilla"),
+                filesystem_access: true,
                 ..Default::default()
             })),
+            instructions_used: Arc::new(AtomicU64::new(0)),
+            lua: None,
         }
     }
 
+    /// Root that script-supplied paths are resolved and sandboxed against
+    pub fn set_base_path(&self, base_path: PathBuf) {
+        self.data.lock().unwrap().base_path = base_path;
+    }
+
+    /// Give scripts a fresh instruction budget, e.g. before running per-frame callbacks
+    pub fn reset_instruction_budget(&self) {
+        self.instructions_used.store(0, Ordering::Relaxed);
+    }
+
+    /// Tile layers in draw order; the renderer relies on this never being re-sorted
     pub fn get_tile_layers(&self) -> Vec<String> {
         self.data.lock().unwrap().tile_layers.clone()
     }
 
+    /// Entity layers in draw order, like `get_tile_layers`
     pub fn get_entity_layers(&self) -> Vec<String> {
         self.data.lock().unwrap().entity_layers.clone()
     }
 
-    pub fn get_mechanics_island_data(&self) -> Option<MechanicsIslandData> {
-        let data = self.data.lock().unwrap();
-        data.island_config
-            .as_ref()
-            .map(|config| MechanicsIslandData::new(config.clone(), data.rooms.clone()))
+    /// Topology snapshot of the loaded island, shared between calls until scripts change it
+    pub fn get_mechanics_island_data(&self) -> Option<Arc<MechanicsIslandData>> {
+        let mut data = self.data.lock().unwrap();
+        if data.mechanics_cache.is_none() {
+            let config = data.island_config.clone()?;
+            let mut mechanics_data = MechanicsIslandData::new(config, data.rooms.clone());
+            mechanics_data.entity_spawns = data.entity_spawns.clone();
+            mechanics_data.world_wrap = data.world_wrap;
+            mechanics_data.tile_costs = data.tile_costs.clone();
+            data.mechanics_cache = Some(Arc::new(mechanics_data));
+        }
+        data.mechanics_cache.clone()
+    }
+
+    /// Collect every problem that prevents the island from being played
+    pub fn validate(&self) -> Vec<String> {
+        let _timer = self.load_timer(LoadPhase::Validation, "island");
+        let mut problems = Vec::new();
+        match self.get_mechanics_island_data() {
+            Some(mechanics_data) => {
+                problems.extend(dock_problem(&mechanics_data));
+                problems.extend(dangling_door_problems(&mechanics_data));
+                problems.extend(looping_door_problems(&mechanics_data));
+            }
+            None => problems.push("Island config not loaded".to_string()),
+        }
+        problems.extend(self.data.lock().unwrap().spawn_problems());
+        problems
+    }
+
+    /// Run one frame of `room_id`'s process callback with `delta` seconds elapsed.
+    /// A room registered with its own `process` runs that instead of the global function.
+    pub fn tick(&self, lua: &Lua, room_id: u32, delta: f64) -> mlua::Result<()> {
+        self.run_process_callback(lua, room_id, delta, |data| {
+            data.room_process_fns
+                .get(&room_id)
+                .or(data.process_fn.as_ref())
+        })
+    }
+
+    /// Physics counterpart of `tick`, using the `physics_process` callbacks
+    pub fn physics_tick(&self, lua: &Lua, room_id: u32, delta: f64) -> mlua::Result<()> {
+        self.run_process_callback(lua, room_id, delta, |data| {
+            data.room_physics_process_fns
+                .get(&room_id)
+                .or(data.physics_process_fn.as_ref())
+        })
+    }
+
+    /// Tick every registered room in registration order using the retained Lua
+    pub fn call_process(&self, delta: f64) -> mlua::Result<()> {
+        let lua = self.lua.as_ref().ok_or_else(|| {
+            LuaError::RuntimeError("Island is not attached to a Lua state".to_string())
+        })?;
+        let room_ids: Vec<u32> = self
+            .data
+            .lock()
+            .unwrap()
+            .rooms
+            .iter()
+            .map(|room| room.room_id)
+            .collect();
+        for room_id in room_ids {
+            self.tick(lua, room_id, delta)?;
+        }
+        Ok(())
+    }
+
+    fn run_process_callback(
+        &self,
+        lua: &Lua,
+        room_id: u32,
+        delta: f64,
+        select: impl Fn(&IslandData) -> Option<&mlua::RegistryKey>,
+    ) -> mlua::Result<()> {
+        // Resolve and release the lock before calling, since callbacks use the island too
+        let callback = {
+            let data = self.data.lock().unwrap();
+            match select(&data) {
+                Some(key) => lua.registry_value::<Function>(key)?,
+                None => return Ok(()),
+            }
+        };
+        self.reset_instruction_budget();
+        callback.call::<()>((delta, room_id))
+    }
+
+    /// Call the `register_loaded_fn` callbacks with the island, once everything is
+    /// registered. Each callback runs once; ones registered after this wait for the next call.
+    pub fn finalize(&self) -> mlua::Result<()> {
+        let lua = self.lua.as_ref().ok_or_else(|| {
+            LuaError::RuntimeError("Island is not attached to a Lua state".to_string())
+        })?;
+        self.run_loaded_fns(lua)
+    }
+
+    fn run_loaded_fns(&self, lua: &Lua) -> mlua::Result<()> {
+        // Take the keys and release the lock before calling, since callbacks use the island too
+        let keys = std::mem::take(&mut self.data.lock().unwrap().loaded_fns);
+        let callbacks = keys
+            .iter()
+            .map(|key| lua.registry_value::<Function>(key))
+            .collect::<mlua::Result<Vec<_>>>()?;
+        for callback in callbacks {
+            // Same handle as the `island` global, which doesn't carry the Lua either
+            callback.call::<()>(Island {
+                lua: None,
+                ..self.clone()
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Start timing a loader call against `phase`; the time is added when the timer drops.
+    /// Take it before locking `data`, so it's released again by then.
+    fn load_timer(&self, phase: LoadPhase, source: &str) -> LoadTimer<'_> {
+        LoadTimer {
+            island: self,
+            phase,
+            source: source.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    fn require_mechanics_island_data(&self) -> mlua::Result<Arc<MechanicsIslandData>> {
+        self.get_mechanics_island_data()
+            .ok_or_else(|| IslandError::Validation("Island config not loaded".to_string()).into())
     }
 }
 
@@ -104,8 +942,50 @@ impl UserData for Island {
             Ok(())
         });
 
+        methods.add_method(
+            "add_tile_layer",
+            |_lua, this, (name, index): (String, Option<i64>)| {
+                let mut data = this.data.lock().unwrap();
+                add_layer(&mut data.tile_layers, "Tile", name, index)
+            },
+        );
+
+        methods.add_method("remove_tile_layer", |_lua, this, name: String| {
+            let mut data = this.data.lock().unwrap();
+            remove_layer(&mut data.tile_layers, "Tile", &name)
+        });
+
+        methods.add_method(
+            "move_tile_layer",
+            |_lua, this, (name, index): (String, i64)| {
+                let mut data = this.data.lock().unwrap();
+                move_layer(&mut data.tile_layers, "Tile", &name, index)
+            },
+        );
+
+        methods.add_method(
+            "add_entity_layer",
+            |_lua, this, (name, index): (String, Option<i64>)| {
+                let mut data = this.data.lock().unwrap();
+                add_layer(&mut data.entity_layers, "Entity", name, index)
+            },
+        );
+
+        methods.add_method("remove_entity_layer", |_lua, this, name: String| {
+            let mut data = this.data.lock().unwrap();
+            remove_layer(&mut data.entity_layers, "Entity", &name)
+        });
+
+        methods.add_method(
+            "move_entity_layer",
+            |_lua, this, (name, index): (String, i64)| {
+                let mut data = this.data.lock().unwrap();
+                move_layer(&mut data.entity_layers, "Entity", &name, index)
+            },
+        );
+
         methods.add_method("register_tile_field", |_lua, this, (tile_type, field_name, field_type, options): (String, String, String, Table)| {
-            let field_options = parse_field_options(options)?;
+            let field_options = parse_field_options(&field_type, options)?;
             let registration = FieldRegistration {
                 field_name,
                 field_type,
@@ -118,7 +998,7 @@ impl UserData for Island {
         });
 
         methods.add_method("register_entity_field", |_lua, this, (entity_type, field_name, field_type, options): (String, String, String, Table)| {
-            let field_options = parse_field_options(options)?;
+            let field_options = parse_field_options(&field_type, options)?;
             let registration = FieldRegistration {
                 field_name,
                 field_type,
@@ -130,43 +1010,141 @@ impl UserData for Island {
             Ok(())
         });
 
-        methods.add_method("load_island_config", |_lua, this, path: String| {
+        methods.add_method("get_tile_fields", |_lua, this, tile_type: String| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .tile_fields
+                .get(&tile_type)
+                .cloned()
+                .unwrap_or_default())
+        });
+
+        // Type and model names come out of HashMaps, so they're sorted to stay stable across calls
+        methods.add_method("get_tile_types", |_lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            let mut types: Vec<String> = data.tile_fields.keys().cloned().collect();
+            types.sort();
+            Ok(types)
+        });
+
+        methods.add_method("get_entity_types", |_lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            let mut types: Vec<String> = data.entity_fields.keys().cloned().collect();
+            types.sort();
+            Ok(types)
+        });
+
+        methods.add_method("get_entity_fields", |_lua, this, entity_type: String| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .entity_fields
+                .get(&entity_type)
+                .cloned()
+                .unwrap_or_default())
+        });
+
+        methods.add_method(
+            "load_island_config",
+            |_lua, this, (path, options): (String, Option<Table>)| {
+                let _timer = this.load_timer(LoadPhase::Config, &path);
+                let merge = match &options {
+                    Some(options) => options.get::<Option<bool>>("merge")?.unwrap_or(false),
+                    None => false,
+                };
+                let mut data = this.data.lock().unwrap();
+                let full_path = data.resolve_existing_path(&path)?;
+                let content = std::fs::read_to_string(&full_path).map_err(IslandError::io(
+                    format!("Failed to read island config from {}", path),
+                ))?;
+
+                if merge {
+                    let base = data.island_config.as_mut().ok_or_else(|| {
+                        IslandError::Validation(format!(
+                            "Cannot merge island config {}: no base island config loaded",
+                            path
+                        ))
+                    })?;
+                    let patch: IslandPatch = ron::Options::default()
+                        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                        .from_str(&content)
+                        .map_err(IslandError::ron_parse("island config", &path))?;
+                    base.apply_patch(patch);
+                    data.invalidate_mechanics_cache();
+                    return Ok(());
+                }
+
+                let island: MechanicsIsland = ron::from_str(&content)
+                    .map_err(IslandError::ron_parse("island config", &path))?;
+                check_schema_version(island.version).map_err(|e| {
+                    IslandError::Validation(format!("Island config {}: {}", path, e))
+                })?;
+                let island = island.migrate();
+
+                // Note: instead of doing full_path.parent() just use data.base_path around line 143.
+                // (Note: The room loading loop that used ron_dir was removed as we now use explicit register_room calls)
+                let _ron_dir = &data.base_path;
+
+                data.island_config = Some(island);
+                data.invalidate_mechanics_cache();
+                Ok(())
+            },
+        );
+
+        // Pre-baked islands from `to_bincode` replace the config, rooms, spawns, world wrap and
+        // tile costs in one go; layers, fields and callbacks registered so far are kept
+        methods.add_method("load_island_binary", |_lua, this, path: String| {
+            let _timer = this.load_timer(LoadPhase::Config, &path);
             let mut data = this.data.lock().unwrap();
-            let full_path = validate_path(Path::new(&path), &data.base_path)
-                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
-            let content = std::fs::read_to_string(&full_path).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to read island config from {}: {}", path, e))
-            })?;
-            let island: MechanicsIsland = ron::from_str(&content).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to parse island config: {}", e))
+            let full_path = data.resolve_existing_path(&path)?;
+            let bytes = std::fs::read(&full_path).map_err(IslandError::io(format!(
+                "Failed to read binary island {}",
+                path
+            )))?;
+            let mut island_data = MechanicsIslandData::from_bincode(&bytes).map_err(|message| {
+                IslandError::BinaryParse {
+                    path: path.clone(),
+                    message,
+                }
             })?;
 
-            // Note: instead of doing full_path.parent() just use data.base_path around line 143.
-            // (Note: The room loading loop that used ron_dir was removed as we now use explicit register_room calls)
-            let _ron_dir = &data.base_path;
-
-            data.island_config = Some(island);
+            *data.rooms_mut() = std::mem::take(island_data.rooms_mut());
+            data.island_config = Some(island_data.island);
+            data.entity_spawns = island_data.entity_spawns;
+            data.world_wrap = island_data.world_wrap;
+            data.tile_costs = island_data.tile_costs;
             Ok(())
         });
 
         methods.add_method("load_entity_spawn", |_lua, this, path: String| {
+            let _timer = this.load_timer(LoadPhase::Spawns, &path);
             let mut data = this.data.lock().unwrap();
-            let full_path = validate_path(Path::new(&path), &data.base_path)
-                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
-            let content = std::fs::read_to_string(&full_path).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to read entity spawn from {}: {}", path, e))
-            })?;
-            let spawn: EntitySpawn = ron::from_str(&content).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to parse entity spawn: {}", e))
-            })?;
+            let full_path = data.resolve_existing_path(&path)?;
+            let content = std::fs::read_to_string(&full_path).map_err(IslandError::io(format!(
+                "Failed to read entity spawn from {}",
+                path
+            )))?;
+            let spawn: EntitySpawn =
+                ron::from_str(&content).map_err(IslandError::ron_parse("entity spawn", &path))?;
             data.entity_spawns.push(spawn);
+            data.invalidate_mechanics_cache();
             Ok(())
         });
 
+        methods.add_method("load_entity_spawns_from_dir", |_lua, this, dir: String| {
+            let _timer = this.load_timer(LoadPhase::Spawns, &dir);
+            let mut data = this.data.lock().unwrap();
+            let spawns: Vec<EntitySpawn> = data.read_ron_dir(&dir, "entity spawn", Ok)?;
+
+            let count = spawns.len();
+            data.entity_spawns.extend(spawns);
+            data.invalidate_mechanics_cache();
+            Ok(count)
+        });
+
         methods.add_method("register_process_fn", |lua, this, func: Function| {
             let mut data = this.data.lock().unwrap();
             let key = lua.create_registry_value(func)?;
-            // TODO: if a room has functions set then it acts as a replacement and the global function isn't run.
+            // Rooms registered with their own `process` replace this one, see `Island::tick`
             data.process_fn = Some(key);
             Ok(())
         });
@@ -176,495 +1154,4328 @@ impl UserData for Island {
             |lua, this, func: Function| {
                 let mut data = this.data.lock().unwrap();
                 let key = lua.create_registry_value(func)?;
-                // TODO: if a room has functions set then it acts as a replacement and the global function isn't run.
+                // Rooms registered with their own `physics_process` replace this one
                 data.physics_process_fn = Some(key);
                 Ok(())
             },
         );
 
-        methods.add_method("register_room", |lua, this, (path, options): (String, Table)| {
-            let mut data = this.data.lock().unwrap();
-            let full_path = validate_path(Path::new(&path), &data.base_path)
-                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
-            let room_content = std::fs::read_to_string(&full_path).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to read room file {}: {}", path, e))
-            })?;
-            let room: Room = ron::from_str(&room_content).map_err(|e| {
-                LuaError::RuntimeError(format!("Failed to parse room file {}: {}", path, e))
+        methods.add_method("register_loaded_fn", |lua, this, func: Function| {
+            let key = lua.create_registry_value(func)?;
+            this.data.lock().unwrap().loaded_fns.push(key);
+            Ok(())
+        });
+
+        methods.add_method("finalize", |lua, this, ()| this.run_loaded_fns(lua));
+
+        methods.add_method(
+            "register_room",
+            |lua, this, (path, options): (String, Table)| {
+                let _timer = this.load_timer(LoadPhase::Rooms, &path);
+                let callbacks = RoomCallbacks::from_options(lua, &options)?;
+                let mut data = this.data.lock().unwrap();
+                let room = data.read_room(&path)?;
+                let room_id = room.room_id;
+                data.insert_room(room)?;
+                callbacks.register(&mut data, room_id);
+                Ok(())
+            },
+        );
+
+        // `register_room` for rooms built in Luau, e.g. by a generator, instead of read from disk
+        methods.add_method(
+            "register_room_table",
+            |lua, this, (room_table, options): (Table, Table)| {
+                let _timer = this.load_timer(LoadPhase::Rooms, "room table");
+                let room = room_from_table(&room_table)?;
+                room.check_tile_bounds()
+                    .map_err(|e| IslandError::Validation(format!("Room table: {}", e)))?;
+                let callbacks = RoomCallbacks::from_options(lua, &options)?;
+
+                let mut data = this.data.lock().unwrap();
+                let room_id = room.room_id;
+                data.insert_room(room)?;
+                callbacks.register(&mut data, room_id);
+                Ok(())
+            },
+        );
+
+        // Live editing: swap in a re-read room file, keeping its callbacks and every other room
+        methods.add_method(
+            "reload_room",
+            |_lua, this, (room_id, path): (u32, String)| {
+                let _timer = this.load_timer(LoadPhase::Rooms, &path);
+                let mut data = this.data.lock().unwrap();
+                let room = data.read_room(&path)?;
+                if room.room_id != room_id {
+                    return Err(IslandError::Validation(format!(
+                        "Room file {} holds room {}, not room {}",
+                        path, room.room_id, room_id
+                    ))
+                    .into());
+                }
+                data.replace_room(room)?;
+                Ok(())
+            },
+        );
+
+        methods.add_method("register_rooms_from_dir", |_lua, this, dir: String| {
+            let _timer = this.load_timer(LoadPhase::Rooms, &dir);
+            let mut data = this.data.lock().unwrap();
+            let rooms = data.read_ron_dir(&dir, "room", |room: Room| {
+                check_schema_version(room.version).and_then(|()| room.check_tile_bounds())?;
+                Ok(room.migrate())
             })?;
-            
-            let room_id = room.room_id;
-            data.rooms.push(room);
 
-            if let Some(process_fn) = options.get::<Option<Function>>("process")? {
-                data.room_process_fns.insert(room_id, lua.create_registry_value(process_fn)?);
-            }
-            if let Some(physics_process_fn) = options.get::<Option<Function>>("physics_process")? {
-                data.room_physics_process_fns.insert(room_id, lua.create_registry_value(physics_process_fn)?);
-            }
-            Ok(())
+            let count = rooms.len();
+            data.insert_rooms(rooms)?;
+            Ok(count)
         });
 
         methods.add_method(
             "register_gltf",
             |_lua, this, (name, path): (String, String)| {
-                validate_filename(&name)
-                    .map_err(|e| LuaError::RuntimeError(format!("Invalid GLTF name: {}", e)))?;
+                validate_filename(&name).map_err(|e| {
+                    IslandError::path(
+                        &name,
+                        PathRejection::Invalid(format!("invalid GLTF name: {}", e)),
+                    )
+                })?;
                 let mut data = this.data.lock().unwrap();
-                let fullpath = validate_path(Path::new(&path), &data.base_path).unwrap();
+                let fullpath = data.resolve_path(&path)?;
+                let is_model = fullpath
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|ext| GLTF_EXTENSIONS.iter().any(|m| ext.eq_ignore_ascii_case(m)));
+                if !is_model {
+                    return Err(IslandError::Validation(format!(
+                        "GLTF file for {} must end in .gltf or .glb: {}",
+                        name, path
+                    ))
+                    .into());
+                }
+                if !fullpath.is_file() {
+                    return Err(IslandError::Validation(format!(
+                        "GLTF file for {} not found: {}",
+                        name, path
+                    ))
+                    .into());
+                }
                 data.gltf_registry.insert(name, fullpath);
                 Ok(())
             },
         );
 
+        methods.add_method("get_gltf_names", |_lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            let mut names: Vec<String> = data.gltf_registry.keys().cloned().collect();
+            names.sort();
+            Ok(names)
+        });
+
+        methods.add_method("has_gltf", |_lua, this, name: String| {
+            Ok(this.data.lock().unwrap().gltf_registry.contains_key(&name))
+        });
+
+        methods.add_method(
+            "register_tile_palette",
+            |_lua, this, (palette_index, gltf_name): (PaletteIndex, String)| {
+                let mut data = this.data.lock().unwrap();
+                if !data.gltf_registry.contains_key(&gltf_name) {
+                    return Err(IslandError::Validation(format!(
+                        "Tile palette {} uses unregistered GLTF model {}",
+                        palette_index, gltf_name
+                    ))
+                    .into());
+                }
+                data.tile_palette.insert(palette_index, gltf_name);
+                Ok(())
+            },
+        );
+
+        methods.add_method(
+            "get_tile_model",
+            |_lua, this, palette_index: PaletteIndex| {
+                Ok(this
+                    .data
+                    .lock()
+                    .unwrap()
+                    .tile_palette
+                    .get(&palette_index)
+                    .cloned())
+            },
+        );
+
+        methods.add_method("set_seed", |_lua, this, seed: i64| {
+            this.data.lock().unwrap().rng = IslandRng::new(seed as u64);
+            Ok(())
+        });
+
+        methods.add_method("random", |_lua, this, ()| {
+            Ok(this.data.lock().unwrap().rng.next_f64())
+        });
+
+        methods.add_method("random_int", |_lua, this, (min, max): (i64, i64)| {
+            if min > max {
+                return Err(LuaError::RuntimeError(format!(
+                    "random_int: min {} is greater than max {}",
+                    min, max
+                )));
+            }
+            Ok(this.data.lock().unwrap().rng.range_i64(min, max))
+        });
+
+        methods.add_method("get_dock_room_id", |_lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .island_config
+                .as_ref()
+                .map(|config| config.dock_room_id))
+        });
+
+        // Attribution from the config's `metadata` block; nil until a config is loaded
+        methods.add_method("get_metadata", |_lua, this, key: String| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .island_config
+                .as_ref()
+                .and_then(|config| config.metadata.get(&key).cloned()))
+        });
+
+        methods.add_method("get_metadata_all", |_lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .island_config
+                .as_ref()
+                .map(|config| config.metadata.clone()))
+        });
+
+        methods.add_method("is_dock_room", |_lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            let config = data
+                .island_config
+                .as_ref()
+                .ok_or_else(|| LuaError::RuntimeError("Island config not loaded".to_string()))?;
+            Ok(config.dock_room_id == room_id)
+        });
+
         methods.add_method("get_room_count", |_lua, this, ()| {
             let data = this.data.lock().unwrap();
             Ok(data.rooms.len())
         });
 
+        methods.add_method("get_rooms_with_tag", |_lua, this, tag: String| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .rooms
+                .iter()
+                .filter(|room| room.tags.contains(&tag))
+                .map(|room| room.room_id)
+                .collect::<Vec<u32>>())
+        });
+
+        methods.add_method("get_total_tile_count", |_lua, this, ()| {
+            Ok(this.data.lock().unwrap().tile_count())
+        });
+
+        // One-call summary for the loader screen
+        methods.add_method("get_stats", |lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            let stats = lua.create_table()?;
+            stats.set("rooms", data.rooms.len())?;
+            stats.set("tiles", data.tile_count())?;
+            stats.set("spawns", data.entity_spawns.len())?;
+            stats.set("entity_types", data.entity_fields.len())?;
+            stats.set("tile_types", data.tile_fields.len())?;
+            stats.set("gltf_models", data.gltf_registry.len())?;
+            Ok(stats)
+        });
+
         methods.add_method("get_entity_spawn_count", |_lua, this, ()| {
             let data = this.data.lock().unwrap();
             Ok(data.entity_spawns.len())
         });
 
+        methods.add_method("get_entity_spawns", |lua, this, ()| {
+            let data = this.data.lock().unwrap();
+            let spawns = lua.create_table()?;
+            for spawn in &data.entity_spawns {
+                spawns.push(spawn_to_lua(lua, spawn)?)?;
+            }
+            Ok(spawns)
+        });
+
         methods.add_method(
             "rooms_are_adjacent",
             |_lua, this, (room_a_id, room_b_id): (u32, u32)| {
-                let mechanics_data = this.get_mechanics_island_data().ok_or_else(|| {
-                    LuaError::RuntimeError("Island config not loaded".to_string())
-                })?;
+                let mechanics_data = this.require_mechanics_island_data()?;
                 Ok(mechanics_data.rooms_are_adjacent(room_a_id, room_b_id))
             },
         );
-    }
-}
 
-fn parse_field_options(options: Table) -> mlua::Result<FieldOptions> {
-    let default = options
-        .get::<Option<Value>>("default")?
-        .and_then(|v| match v {
-            Value::Integer(i) => Some(DefaultValue::Int(i)),
-            Value::Number(n) => Some(DefaultValue::Float(n)),
-            Value::String(s) => s.to_str().ok().map(|s| DefaultValue::String(s.to_string())),
-            Value::Boolean(b) => Some(DefaultValue::Bool(b)),
-            _ => None,
+        methods.add_method(
+            "adjacency_reason",
+            |_lua, this, (room_a_id, room_b_id): (u32, u32)| {
+                let mechanics_data = this.require_mechanics_island_data()?;
+                Ok(mechanics_data
+                    .adjacency_reason(room_a_id, room_b_id)
+                    .to_string())
+            },
+        );
+
+        // Assertions for mod self-tests, failing with a message that names what's missing
+        methods.add_method("assert_room_exists", |_lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            if data.rooms.iter().any(|room| room.room_id == room_id) {
+                return Ok(());
+            }
+            let mut room_ids: Vec<u32> = data.rooms.iter().map(|room| room.room_id).collect();
+            room_ids.sort_unstable();
+            let registered: Vec<String> = room_ids.iter().map(u32::to_string).collect();
+            Err(LuaError::RuntimeError(format!(
+                "Assertion failed: room {} is not registered (registered rooms: {})",
+                room_id,
+                list_or_none(&registered)
+            )))
         });
 
-    let min = options.get::<Option<i64>>("min")?;
-    let max = options.get::<Option<i64>>("max")?;
+        methods.add_method(
+            "assert_entity_field",
+            |_lua, this, (entity_type, field_name): (String, String)| {
+                let data = this.data.lock().unwrap();
+                let Some(fields) = data.entity_fields.get(&entity_type) else {
+                    return Err(LuaError::RuntimeError(format!(
+                        "Assertion failed: entity type {} has no registered fields",
+                        entity_type
+                    )));
+                };
+                if fields.iter().any(|field| field.field_name == field_name) {
+                    return Ok(());
+                }
+                let names: Vec<String> = fields
+                    .iter()
+                    .map(|field| field.field_name.clone())
+                    .collect();
+                Err(LuaError::RuntimeError(format!(
+                    "Assertion failed: entity type {} has no field {} (fields: {})",
+                    entity_type,
+                    field_name,
+                    list_or_none(&names)
+                )))
+            },
+        );
 
-    let (values, value_type) = match options.get::<Option<Value>>("values")? {
-        Some(Value::Table(t)) => {
-            let mut vec = Vec::new();
-            for value in t.sequence_values::<String>() {
-                if let Ok(v) = value {
-                    vec.push(v);
+        methods.add_method(
+            "assert_adjacent",
+            |_lua, this, (room_a_id, room_b_id): (u32, u32)| {
+                let mechanics_data = this.require_mechanics_island_data()?;
+                let why_not = match mechanics_data.adjacency_reason(room_a_id, room_b_id) {
+                    AdjacencyReason::Adjacent => return Ok(()),
+                    AdjacencyReason::NotTouching => "they don't share a face".to_string(),
+                    AdjacencyReason::RoomMissing(room_id) => {
+                        format!("room {} is not registered", room_id)
+                    }
+                    AdjacencyReason::SameRoom => "they are the same room".to_string(),
+                };
+                Err(LuaError::RuntimeError(format!(
+                    "Assertion failed: rooms {} and {} are not adjacent, {}",
+                    room_a_id, room_b_id, why_not
+                )))
+            },
+        );
+
+        // `{ x = 200 }` wraps the world every 200 units along x; omitted axes stay unbounded
+        methods.add_method("set_world_wrap", |_lua, this, sizes: Table| {
+            let mut world_wrap = WorldWrap::default();
+            for (axis, size) in [
+                ("x", &mut world_wrap.x),
+                ("y", &mut world_wrap.y),
+                ("z", &mut world_wrap.z),
+            ] {
+                *size = sizes.get::<Option<i64>>(axis)?;
+                if size.is_some_and(|size| size <= 0) {
+                    return Err(LuaError::RuntimeError(format!(
+                        "World wrap size for {} must be positive",
+                        axis
+                    )));
+                }
+            }
+            let mut data = this.data.lock().unwrap();
+            data.world_wrap = world_wrap;
+            data.invalidate_mechanics_cache();
+            Ok(())
+        });
+
+        methods.add_method("room_at", |_lua, this, (x, y, z): (i64, i64, i64)| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            Ok(mechanics_data.room_at(x, y, z))
+        });
+
+        methods.add_method("for_each_world_tile", |lua, this, callback: Function| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            // Data is cloned above, so the callback is free to call back into the island
+            for (x, y, z, tile) in mechanics_data.iter_world_tiles() {
+                callback.call::<()>((x, y, z, tile_to_lua(lua, tile)?))?;
+            }
+            Ok(())
+        });
+
+        // Everything an inspector shows about one room in a single call; nil for unknown ids
+        methods.add_method("get_room_by_id", |lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            let Some(room) = data.rooms.iter().find(|r| r.room_id == room_id) else {
+                return Ok(None);
+            };
+            let axes = |x: Value, y: Value, z: Value| -> mlua::Result<Table> {
+                let axes = lua.create_table()?;
+                axes.set("x", x)?;
+                axes.set("y", y)?;
+                axes.set("z", z)?;
+                Ok(axes)
+            };
+            let is_dock = data
+                .island_config
+                .as_ref()
+                .is_some_and(|config| config.dock_room_id == room_id);
+
+            let info = lua.create_table()?;
+            info.set("room_id", room.room_id)?;
+            info.set(
+                "position",
+                axes(
+                    room.pos_x.into_lua(lua)?,
+                    room.pos_y.into_lua(lua)?,
+                    room.pos_z.into_lua(lua)?,
+                )?,
+            )?;
+            info.set(
+                "extents",
+                axes(
+                    room.extent_x.into_lua(lua)?,
+                    room.extent_y.into_lua(lua)?,
+                    room.extent_z.into_lua(lua)?,
+                )?,
+            )?;
+            info.set(
+                "looping",
+                axes(
+                    Value::Boolean(room.looping_x),
+                    Value::Boolean(room.looping_y),
+                    Value::Boolean(room.looping_z),
+                )?,
+            )?;
+            info.set("tags", room.tags.clone())?;
+            info.set("tile_count", room.tiles.len())?;
+            info.set("is_dock", is_dock)?;
+            Ok(Some(info))
+        });
+
+        methods.add_method("get_room_aabb", |lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            let Some(room) = data.rooms.iter().find(|r| r.room_id == room_id) else {
+                return Ok(None);
+            };
+            Ok(Some(bounds_to_lua(lua, room.aabb())?))
+        });
+
+        // Box around every room, e.g. to frame the camera; nil before any room is registered
+        methods.add_method("get_world_bounds", |lua, this, ()| {
+            match this.require_mechanics_island_data()?.world_bounds() {
+                Some(bounds) => Ok(Some(bounds_to_lua(lua, bounds)?)),
+                None => Ok(None),
+            }
+        });
+
+        // Sized by the same grid `create_grid` builds, so spawn indices can be checked against it
+        methods.add_method("get_room_grid_size", |_lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .rooms
+                .iter()
+                .find(|r| r.room_id == room_id)
+                .map(|room| room.grid_size()))
+        });
+
+        methods.add_method("get_room_extents", |lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            let Some(room) = data.rooms.iter().find(|r| r.room_id == room_id) else {
+                return Ok(Value::Nil);
+            };
+            let extents = lua.create_table()?;
+            extents.set("x", room.extent_x)?;
+            extents.set("y", room.extent_y)?;
+            extents.set("z", room.extent_z)?;
+            Ok(Value::Table(extents))
+        });
+
+        methods.add_method("get_door_indices", |_lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            Ok(data
+                .rooms
+                .iter()
+                .find(|r| r.room_id == room_id)
+                .map(|room| room.tiles_of_type(|tile| matches!(tile, TileData::Door(_, _)))))
+        });
+
+        // Per-room doors for the renderer: where each sits, where it leads and which model it uses
+        methods.add_method("get_room_doors", |lua, this, room_id: u32| {
+            let data = this.data.lock().unwrap();
+            let Some(room) = data.rooms.iter().find(|r| r.room_id == room_id) else {
+                return Ok(None);
+            };
+            let mut room_doors: Vec<_> = room
+                .tiles
+                .iter()
+                .filter_map(|(index, tile)| match tile {
+                    TileData::Door(palette, to) => Some((*index, *to, *palette)),
+                    _ => None,
+                })
+                .collect();
+            room_doors.sort_by_key(|(index, _, _)| *index);
+
+            let doors = lua.create_table()?;
+            for (index, to, palette) in room_doors {
+                let door = lua.create_table()?;
+                door.set("index", index)?;
+                door.set("to", to)?;
+                door.set("palette", palette)?;
+                doors.push(door)?;
+            }
+            Ok(Some(doors))
+        });
+
+        methods.add_method(
+            "get_tile_indices",
+            |_lua, this, (room_id, palette_index): (u32, u32)| {
+                let data = this.data.lock().unwrap();
+                Ok(data
+                    .rooms
+                    .iter()
+                    .find(|r| r.room_id == room_id)
+                    .map(|room| {
+                        room.tiles_of_type(|tile| {
+                            matches!(tile, TileData::Tile(palette, _) if *palette == palette_index)
+                        })
+                    }))
+            },
+        );
+
+        methods.add_method("export_json", |_lua, this, path: String| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            let data = this.data.lock().unwrap();
+            let full_path = data.resolve_path(&path)?;
+
+            let mut exported = mechanics_data.to_json_value();
+            exported["tile_fields"] = serde_json::to_value(&data.tile_fields)
+                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
+            exported["entity_fields"] = serde_json::to_value(&data.entity_fields)
+                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
+            let json = serde_json::to_string_pretty(&exported)
+                .map_err(|e| LuaError::RuntimeError(e.to_string()))?;
+            std::fs::write(&full_path, json).map_err(IslandError::io(format!(
+                "Failed to write JSON export {}",
+                path
+            )))?;
+            Ok(())
+        });
+
+        methods.add_method(
+            "shared_face_area",
+            |_lua, this, (room_a_id, room_b_id): (u32, u32)| {
+                let mechanics_data = this.require_mechanics_island_data()?;
+                Ok(mechanics_data.shared_face_area(room_a_id, room_b_id))
+            },
+        );
+
+        methods.add_method(
+            "tile_neighbors",
+            |_lua, this, (room_id, index): (u32, usize)| {
+                let data = this.data.lock().unwrap();
+                Ok(data
+                    .rooms
+                    .iter()
+                    .find(|r| r.room_id == room_id)
+                    .map(|room| room.neighbors(index)))
+            },
+        );
+
+        methods.add_method(
+            "set_tile_cost",
+            |_lua, this, (palette_index, cost): (PaletteIndex, f64)| {
+                if cost.is_nan() {
+                    return Err(LuaError::RuntimeError(format!(
+                        "Tile cost for palette {} must be a number",
+                        palette_index
+                    )));
+                }
+                let mut data = this.data.lock().unwrap();
+                data.tile_costs.insert(palette_index, cost);
+                data.invalidate_mechanics_cache();
+                Ok(())
+            },
+        );
+
+        // More than one cluster means part of the island can't be reached from the rest
+        methods.add_method("get_room_clusters", |_lua, this, ()| {
+            Ok(this.require_mechanics_island_data()?.connected_components())
+        });
+
+        // Rooms each room reaches by a shared face or one of its doors, keyed by room id
+        methods.add_method("get_adjacency_matrix", |_lua, this, ()| {
+            Ok(this.require_mechanics_island_data()?.adjacency_matrix())
+        });
+
+        // Room route where each step says whether it walks across a face or opens a door
+        methods.add_method("find_path_detailed", |lua, this, (from, to): (u32, u32)| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            let Some(path) = mechanics_data.find_path_detailed(from, to) else {
+                return Ok(Value::Nil);
+            };
+
+            let steps = lua.create_table()?;
+            for PathStep { room_id, via } in path {
+                let step = lua.create_table()?;
+                step.set("room_id", room_id)?;
+                match via {
+                    Edge::Adjacent { face } => {
+                        step.set("via", "adjacent")?;
+                        step.set("face", face.to_string())?;
+                    }
+                    Edge::Door { palette } => {
+                        step.set("via", "door")?;
+                        step.set("palette", palette)?;
+                    }
+                }
+                steps.push(step)?;
+            }
+            Ok(Value::Table(steps))
+        });
+
+        methods.add_method(
+            "find_path_weighted",
+            |lua, this, (from_room, from_index, to_room, to_index): (u32, usize, u32, usize)| {
+                let mechanics_data = this.require_mechanics_island_data()?;
+                let Some((path, cost)) =
+                    mechanics_data.find_path_weighted((from_room, from_index), (to_room, to_index))
+                else {
+                    return Ok(Value::Nil);
+                };
+
+                let steps = lua.create_table()?;
+                for (room_id, index) in path {
+                    let step = lua.create_table()?;
+                    step.set("room", room_id)?;
+                    step.set("index", index)?;
+                    steps.push(step)?;
                 }
+                let result = lua.create_table()?;
+                result.set("cost", cost)?;
+                result.set("steps", steps)?;
+                Ok(Value::Table(result))
+            },
+        );
+
+        // Resolved against the current root, so a script can narrow its sandbox but never widen it
+        methods.add_method("set_base_path", |_lua, this, path: String| {
+            let mut data = this.data.lock().unwrap();
+            let full_path = data.resolve_path(&path)?;
+            if !full_path.exists() {
+                return Err(
+                    IslandError::Validation(format!("Base path {} does not exist", path)).into(),
+                );
             }
-            (Some(vec), None)
+            if !full_path.is_dir() {
+                return Err(IslandError::Validation(format!(
+                    "Base path {} is not a directory",
+                    path
+                ))
+                .into());
+            }
+            data.base_path = full_path;
+            Ok(())
+        });
+
+        methods.add_method("clear", |_lua, this, ()| {
+            this.data.lock().unwrap().clear();
+            Ok(())
+        });
+
+        methods.add_method("validate", |_lua, this, ()| Ok(this.validate()));
+
+        methods.add_method("validate_spawns", |_lua, this, ()| {
+            let _timer = this.load_timer(LoadPhase::Validation, "spawns");
+            Ok(this.data.lock().unwrap().spawn_problems())
+        });
+
+        methods.add_method("get_load_timings", |lua, this, ()| {
+            let timings = this.data.lock().unwrap().load_timings;
+            let table = lua.create_table()?;
+            for phase in LoadPhase::ALL {
+                table.set(phase.name(), timings.get(phase).as_secs_f64() * 1000.0)?;
+            }
+            Ok(table)
+        });
+
+        methods.add_method("check_dock", |_lua, this, ()| {
+            let _timer = this.load_timer(LoadPhase::Validation, "dock");
+            let mechanics_data = this.require_mechanics_island_data()?;
+            match dock_problem(&mechanics_data) {
+                Some(problem) => Err(IslandError::Validation(problem).into()),
+                None => Ok(()),
+            }
+        });
+
+        methods.add_method("check_doors", |_lua, this, ()| {
+            let _timer = this.load_timer(LoadPhase::Validation, "doors");
+            let mechanics_data = this.require_mechanics_island_data()?;
+            let problems = dangling_door_problems(&mechanics_data);
+            if problems.is_empty() {
+                Ok(())
+            } else {
+                Err(LuaError::RuntimeError(problems.join("\n")))
+            }
+        });
+
+        methods.add_method("unpaired_doors", |lua, this, ()| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            let unpaired = lua.create_table()?;
+            for (from, to) in mechanics_data.unpaired_doors() {
+                let door = lua.create_table()?;
+                door.set("from", from)?;
+                door.set("to", to)?;
+                unpaired.push(door)?;
+            }
+            Ok(unpaired)
+        });
+
+        methods.add_method("get_doors", |lua, this, ()| {
+            let mechanics_data = this.require_mechanics_island_data()?;
+            let doors = lua.create_table()?;
+            for (from, to, palette) in mechanics_data.doors() {
+                let door = lua.create_table()?;
+                door.set("from", from)?;
+                door.set("to", to)?;
+                door.set("palette", palette)?;
+                doors.push(door)?;
+            }
+            Ok(doors)
+        });
+    }
+}
+
+/// A tile as a Luau table tagged by `kind`: "none", "tile", "door" or "trigger"
+fn tile_to_lua(lua: &Lua, tile: &TileData) -> mlua::Result<Table> {
+    let table = lua.create_table()?;
+    match tile {
+        TileData::None => {
+            table.set("kind", "none")?;
+        }
+        TileData::Tile(palette, orientation) => {
+            table.set("kind", "tile")?;
+            table.set("palette", *palette)?;
+            table.set("orientation", format!("{:?}", orientation))?;
+        }
+        TileData::Door(palette, to_room) => {
+            table.set("kind", "door")?;
+            table.set("palette", *palette)?;
+            table.set("to_room", *to_room)?;
+        }
+        TileData::Trigger(name) => {
+            table.set("kind", "trigger")?;
+            table.set("name", name.as_str())?;
+        }
+    }
+    Ok(table)
+}
+
+/// The `process` and `physics_process` callbacks from a room's registration options, read
+/// before the room is inserted so a bad option doesn't leave it registered without them
+struct RoomCallbacks {
+    process: Option<mlua::RegistryKey>,
+    physics_process: Option<mlua::RegistryKey>,
+}
+
+impl RoomCallbacks {
+    fn from_options(lua: &Lua, options: &Table) -> mlua::Result<Self> {
+        let callback = |key: &str| -> mlua::Result<Option<mlua::RegistryKey>> {
+            options
+                .get::<Option<Function>>(key)?
+                .map(|func| lua.create_registry_value(func))
+                .transpose()
+        };
+        Ok(RoomCallbacks {
+            process: callback("process")?,
+            physics_process: callback("physics_process")?,
+        })
+    }
+
+    /// Keep the callbacks for `room_id`, which has just been inserted
+    fn register(self, data: &mut IslandData, room_id: u32) {
+        if let Some(key) = self.process {
+            data.room_process_fns.insert(room_id, key);
+        }
+        if let Some(key) = self.physics_process {
+            data.room_physics_process_fns.insert(room_id, key);
         }
-        Some(Value::String(s)) => (None, s.to_str().ok().map(|s| s.to_string())),
-        _ => (None, None),
+    }
+}
+
+/// Build a room from the table form of a room file:
+/// `{ room_id, pos = {x, y, z}, extent = {x, y, z}, looping = {x, y, z}, tiles, tags }`
+/// Missing axes default as they do in RON: position 0, extent 1, not looping
+fn room_from_table(table: &Table) -> mlua::Result<Room> {
+    let room_id: u32 = table
+        .get::<Option<u32>>("room_id")?
+        .ok_or_else(|| IslandError::Validation("Room table has no room_id".to_string()))?;
+
+    let mut tiles = HashMap::new();
+    if let Some(tile_table) = table.get::<Option<Table>>("tiles")? {
+        for pair in tile_table.pairs::<GridIndex, Table>() {
+            let (index, tile) = pair?;
+            tiles.insert(index, tile_from_table(room_id, index, &tile)?);
+        }
+    }
+
+    Ok(Room {
+        version: SCHEMA_VERSION,
+        room_id,
+        pos_x: table_axis(table, "pos", "x")?.unwrap_or(0),
+        pos_y: table_axis(table, "pos", "y")?.unwrap_or(0),
+        pos_z: table_axis(table, "pos", "z")?.unwrap_or(0),
+        extent_x: table_axis(table, "extent", "x")?.unwrap_or(1),
+        extent_y: table_axis(table, "extent", "y")?.unwrap_or(1),
+        extent_z: table_axis(table, "extent", "z")?.unwrap_or(1),
+        looping_x: table_axis(table, "looping", "x")?.unwrap_or(false),
+        looping_y: table_axis(table, "looping", "y")?.unwrap_or(false),
+        looping_z: table_axis(table, "looping", "z")?.unwrap_or(false),
+        tiles,
+        tags: table
+            .get::<Option<Vec<String>>>("tags")?
+            .unwrap_or_default(),
+    })
+}
+
+/// `table[key][axis]`, or None when either is missing
+fn table_axis<T: FromLua>(table: &Table, key: &str, axis: &str) -> mlua::Result<Option<T>> {
+    match table.get::<Option<Table>>(key)? {
+        Some(axes) => axes.get(axis),
+        None => Ok(None),
+    }
+}
+
+/// One `tiles` entry, e.g. `{ kind = "Door", palette = 1, target = 2 }`.
+/// `Tile` takes an optional `orientation` ("North" when left out), `Trigger` a `name`.
+/// The lowercase kinds and `to_room` that `tile_to_lua` produces are accepted too.
+fn tile_from_table(room_id: u32, index: GridIndex, tile: &Table) -> mlua::Result<TileData> {
+    let invalid = |message: String| -> LuaError {
+        IslandError::Validation(format!(
+            "Room table {} tile {}: {}",
+            room_id, index, message
+        ))
+        .into()
     };
+    let missing = |key: &str| invalid(format!("missing {}", key));
 
-    let keys = options.get::<Option<String>>("keys")?;
-    let item_type = options.get::<Option<String>>("item_type")?;
+    let kind: String = tile
+        .get::<Option<String>>("kind")?
+        .ok_or_else(|| missing("kind"))?;
+    match kind.as_str() {
+        "None" | "none" => Ok(TileData::None),
+        "Tile" | "tile" => {
+            let orientation = match tile.get::<Option<String>>("orientation")?.as_deref() {
+                None | Some("North") => Orientation::North,
+                Some("East") => Orientation::East,
+                Some("South") => Orientation::South,
+                Some("West") => Orientation::West,
+                Some(other) => return Err(invalid(format!("unknown orientation {}", other))),
+            };
+            let palette: PaletteIndex = tile
+                .get::<Option<PaletteIndex>>("palette")?
+                .ok_or_else(|| missing("palette"))?;
+            Ok(TileData::Tile(palette, orientation))
+        }
+        "Door" | "door" => {
+            let palette: PaletteIndex = tile
+                .get::<Option<PaletteIndex>>("palette")?
+                .ok_or_else(|| missing("palette"))?;
+            let target: u32 = match tile.get::<Option<u32>>("target")? {
+                Some(target) => target,
+                None => tile
+                    .get::<Option<u32>>("to_room")?
+                    .ok_or_else(|| missing("target"))?,
+            };
+            Ok(TileData::Door(palette, target))
+        }
+        "Trigger" | "trigger" => {
+            let name: String = tile
+                .get::<Option<String>>("name")?
+                .ok_or_else(|| missing("name"))?;
+            Ok(TileData::Trigger(name))
+        }
+        other => Err(invalid(format!(
+            "unknown kind {}, expected None, Tile, Door or Trigger",
+            other
+        ))),
+    }
+}
+
+/// `{ min = {x, y, z}, max = {x, y, z} }` for a room or world AABB
+fn bounds_to_lua(lua: &Lua, (min, max): RoomBounds) -> mlua::Result<Table> {
+    let corner = |(x, y, z): (i64, i64, i64)| -> mlua::Result<Table> {
+        let corner = lua.create_table()?;
+        corner.set("x", x)?;
+        corner.set("y", y)?;
+        corner.set("z", z)?;
+        Ok(corner)
+    };
+    let bounds = lua.create_table()?;
+    bounds.set("min", corner(min)?)?;
+    bounds.set("max", corner(max)?)?;
+    Ok(bounds)
+}
+
+/// Where a spawn sits, for naming it in validation messages
+fn spawn_location(spawn: &EntitySpawn) -> String {
+    format!(
+        "Spawn {} in room {} at grid index {}",
+        spawn.entity_type, spawn.room_id, spawn.grid_index
+    )
+}
+
+/// A spawn as a Luau table, its properties nested as the raw strings from the file
+fn spawn_to_lua(lua: &Lua, spawn: &EntitySpawn) -> mlua::Result<Table> {
+    let table = lua.create_table()?;
+    table.set("id", spawn.id.as_deref())?;
+    table.set("entity_type", spawn.entity_type.as_str())?;
+    table.set("room_id", spawn.room_id)?;
+    table.set("grid_index", spawn.grid_index)?;
+    let properties = lua.create_table()?;
+    for (key, value) in &spawn.properties {
+        properties.set(key.as_str(), value.as_str())?;
+    }
+    table.set("properties", properties)?;
+    table.set("rotation", spawn.rotation)?;
+    table.set("scale", spawn.scale)?;
+    Ok(table)
+}
+
+/// Check a spawn property string parses as its field's declared type.
+/// Lists and maps are written as RON, vector3 as `x,y,z`, entity_ref as the target spawn's id;
+/// whether that spawn exists is checked by `spawn_problems`, which can see all of them.
+fn check_property_value(field: &FieldRegistration, raw: &str) -> Result<(), String> {
+    let options = &field.options;
+    match field.field_type.as_str() {
+        "int" => {
+            let value: i64 = raw
+                .trim()
+                .parse()
+                .map_err(|_| format!("expected int, got {:?}", raw))?;
+            if options.min.is_some_and(|min| value < min)
+                || options.max.is_some_and(|max| value > max)
+            {
+                return Err(format!(
+                    "{} is outside the range {}..={}",
+                    value,
+                    options.min.map_or("".to_string(), |m| m.to_string()),
+                    options.max.map_or("".to_string(), |m| m.to_string())
+                ));
+            }
+            Ok(())
+        }
+        "float" => raw
+            .trim()
+            .parse::<f64>()
+            .map(|_| ())
+            .map_err(|_| format!("expected float, got {:?}", raw)),
+        "bool" => match raw.trim() {
+            "true" | "false" => Ok(()),
+            _ => Err(format!("expected bool, got {:?}", raw)),
+        },
+        "enum" => {
+            let values = options.values.as_deref().unwrap_or_default();
+            let is_stored_value = raw.trim().parse::<i64>().is_ok_and(|raw_value| {
+                options
+                    .enum_values
+                    .iter()
+                    .flatten()
+                    .any(|(_, value)| *value == raw_value)
+            });
+            if values.iter().any(|v| v == raw) || is_stored_value {
+                Ok(())
+            } else {
+                Err(format!("{:?} is not one of {}", raw, values.join(", ")))
+            }
+        }
+        "color" => parse_hex_color(raw.trim())
+            .map(|_| ())
+            .ok_or_else(|| format!("expected #RRGGBB or #RRGGBBAA, got {:?}", raw)),
+        "vector3" => {
+            let components: Vec<&str> = raw.split(',').collect();
+            if components.len() == 3 && components.iter().all(|c| c.trim().parse::<f64>().is_ok()) {
+                Ok(())
+            } else {
+                Err(format!("expected x,y,z, got {:?}", raw))
+            }
+        }
+        "entity_ref" if raw.trim().is_empty() => Err("expected a spawn id, got \"\"".to_string()),
+        "list" => match ron::from_str::<ron::Value>(raw) {
+            Ok(ron::Value::Seq(_)) => Ok(()),
+            _ => Err(format!("expected a RON list, got {:?}", raw)),
+        },
+        "map" => match ron::from_str::<ron::Value>(raw) {
+            Ok(ron::Value::Map(map)) => {
+                let key_matches = |key: &ron::Value| match options.keys.as_deref() {
+                    Some("string") => matches!(key, ron::Value::String(_)),
+                    Some("int") => matches!(key, ron::Value::Number(ron::Number::Integer(_))),
+                    _ => true,
+                };
+                match map.iter().map(|(key, _)| key).find(|key| !key_matches(key)) {
+                    Some(key) => Err(format!(
+                        "map key {} is not {}",
+                        ron::to_string(key).unwrap_or_default(),
+                        options.keys.as_deref().unwrap_or_default()
+                    )),
+                    None => Ok(()),
+                }
+            }
+            _ => Err(format!("expected a RON map, got {:?}", raw)),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Insert `name` at the 1-based `index`, Lua list style, or append it when no index is given
+fn add_layer(
+    layers: &mut Vec<String>,
+    kind: &str,
+    name: String,
+    index: Option<i64>,
+) -> mlua::Result<()> {
+    if layers.contains(&name) {
+        return Err(LuaError::RuntimeError(format!(
+            "{} layer {} already exists",
+            kind, name
+        )));
+    }
+    let position = match index {
+        Some(index) => layer_position(index, layers.len() + 1)?,
+        None => layers.len(),
+    };
+    layers.insert(position, name);
+    Ok(())
+}
+
+fn remove_layer(layers: &mut Vec<String>, kind: &str, name: &str) -> mlua::Result<()> {
+    let position = find_layer(layers, kind, name)?;
+    layers.remove(position);
+    Ok(())
+}
+
+/// Move `name` so it ends up at the 1-based `new_index`, shifting the layers in between
+fn move_layer(
+    layers: &mut Vec<String>,
+    kind: &str,
+    name: &str,
+    new_index: i64,
+) -> mlua::Result<()> {
+    let position = find_layer(layers, kind, name)?;
+    let new_position = layer_position(new_index, layers.len())?;
+    let layer = layers.remove(position);
+    layers.insert(new_position, layer);
+    Ok(())
+}
+
+fn find_layer(layers: &[String], kind: &str, name: &str) -> mlua::Result<usize> {
+    layers
+        .iter()
+        .position(|layer| layer == name)
+        .ok_or_else(|| LuaError::RuntimeError(format!("{} layer {} does not exist", kind, name)))
+}
+
+/// Zero-based position for a 1-based `index` that may be at most `max`
+fn layer_position(index: i64, max: usize) -> mlua::Result<usize> {
+    if index < 1 || index as u64 > max as u64 {
+        return Err(LuaError::RuntimeError(format!(
+            "Layer index {} is out of range 1..={}",
+            index, max
+        )));
+    }
+    Ok(index as usize - 1)
+}
+
+/// Players spawn in the dock room, so the island can't start unless it is registered
+fn dock_problem(mechanics_data: &MechanicsIslandData) -> Option<String> {
+    let dock_room_id = mechanics_data.island.dock_room_id;
+    let registered = mechanics_data
+        .rooms()
+        .iter()
+        .any(|room| room.room_id == dock_room_id);
+    (!registered).then(|| format!("Dock room {} is not registered", dock_room_id))
+}
+
+fn dangling_door_problems(mechanics_data: &MechanicsIslandData) -> Vec<String> {
+    mechanics_data
+        .dangling_doors()
+        .into_iter()
+        .map(|(room_id, index, target)| {
+            format!(
+                "Door in room {} at grid index {} targets missing room {}",
+                room_id, index, target
+            )
+        })
+        .collect()
+}
+
+/// Comma-separated items for an error message, or "none"
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn looping_door_problems(mechanics_data: &MechanicsIslandData) -> Vec<String> {
+    mechanics_data
+        .doors_on_looping_faces()
+        .into_iter()
+        .map(|(room_id, index)| {
+            format!(
+                "Warning: door in room {} at grid index {} is on a looping face, \
+                 where wrapping leaves no other side",
+                room_id, index
+            )
+        })
+        .collect()
+}
+
+fn parse_scalar_default(value: Value) -> Option<DefaultValue> {
+    match value {
+        Value::Integer(i) => Some(DefaultValue::Int(i)),
+        Value::Number(n) => Some(DefaultValue::Float(n)),
+        Value::String(s) => s.to_str().ok().map(|s| DefaultValue::String(s.to_string())),
+        Value::Boolean(b) => Some(DefaultValue::Bool(b)),
+        _ => None,
+    }
+}
+
+/// Parse `#RRGGBB` or `#RRGGBBAA`, with alpha defaulting to opaque
+fn parse_hex_color(hex: &str) -> Option<[u8; 4]> {
+    let digits = hex.strip_prefix('#')?;
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    let alpha = if digits.len() == 8 { channel(6)? } else { 255 };
+    Some([channel(0)?, channel(2)?, channel(4)?, alpha])
+}
+
+fn parse_default_value(field_type: &str, value: Value) -> mlua::Result<Option<DefaultValue>> {
+    match (field_type, value) {
+        ("color", value) => {
+            let hex = match &value {
+                Value::String(s) => s.to_str().ok().map(|s| s.to_string()),
+                _ => None,
+            };
+            hex.as_deref()
+                .and_then(parse_hex_color)
+                .map(|rgba| Some(DefaultValue::Color(rgba)))
+                .ok_or_else(|| {
+                    LuaError::RuntimeError(format!(
+                        "Invalid color default {:?}: expected #RRGGBB or #RRGGBBAA",
+                        hex.unwrap_or_else(|| value.type_name().to_string())
+                    ))
+                })
+        }
+        ("vector3", Value::Table(t)) => {
+            if t.pairs::<Value, Value>().count() != 3 {
+                return Err(LuaError::RuntimeError(
+                    "vector3 default must be a table of exactly x, y and z".to_string(),
+                ));
+            }
+            let component = |axis: &str| {
+                t.get::<Option<f64>>(axis)?.ok_or_else(|| {
+                    LuaError::RuntimeError(format!("vector3 default is missing component {}", axis))
+                })
+            };
+            Ok(Some(DefaultValue::Vec3(
+                component("x")?,
+                component("y")?,
+                component("z")?,
+            )))
+        }
+        ("vector3", value) => Err(LuaError::RuntimeError(format!(
+            "vector3 default must be a table of x, y and z, got {}",
+            value.type_name()
+        ))),
+        ("list", Value::Table(t)) => {
+            let mut items = Vec::new();
+            for (index, item) in t.sequence_values::<Value>().enumerate() {
+                let item = item?;
+                let type_name = item.type_name();
+                items.push(parse_scalar_default(item).ok_or_else(|| {
+                    LuaError::RuntimeError(format!(
+                        "list default item {} is a {}, expected a string, number or boolean",
+                        index + 1,
+                        type_name
+                    ))
+                })?);
+            }
+            Ok(Some(DefaultValue::List(items)))
+        }
+        ("map", Value::Table(t)) => {
+            let mut entries = HashMap::new();
+            for pair in t.pairs::<String, Value>() {
+                let (key, value) = pair?;
+                let type_name = value.type_name();
+                let value = parse_scalar_default(value).ok_or_else(|| {
+                    LuaError::RuntimeError(format!(
+                        "map default value for {:?} is a {}, expected a string, number or boolean",
+                        key, type_name
+                    ))
+                })?;
+                entries.insert(key, value);
+            }
+            Ok(Some(DefaultValue::Map(entries)))
+        }
+        (_, value) => Ok(parse_scalar_default(value)),
+    }
+}
+
+fn parse_field_options(field_type: &str, options: Table) -> mlua::Result<FieldOptions> {
+    let mut default = match options.get::<Option<Value>>("default")? {
+        Some(value) => parse_default_value(field_type, value)?,
+        None => None,
+    };
+
+    let min = options.get::<Option<i64>>("min")?;
+    let max = options.get::<Option<i64>>("max")?;
+
+    // min/max bound each component of a vector independently
+    if let Some(DefaultValue::Vec3(x, y, z)) = &mut default {
+        for component in [x, y, z] {
+            if let Some(min) = min {
+                *component = component.max(min as f64);
+            }
+            if let Some(max) = max {
+                *component = component.min(max as f64);
+            }
+        }
+    }
+
+    // An int default outside min/max is an error unless the field opts into `clamp = true`
+    if let Some(DefaultValue::Int(value)) = &mut default {
+        let clamped = (*value)
+            .max(min.unwrap_or(i64::MIN))
+            .min(max.unwrap_or(i64::MAX));
+        if clamped != *value {
+            let range = format!(
+                "{}..={}",
+                min.map_or("".to_string(), |m| m.to_string()),
+                max.map_or("".to_string(), |m| m.to_string())
+            );
+            if !options.get::<Option<bool>>("clamp")?.unwrap_or(false) {
+                return Err(LuaError::RuntimeError(format!(
+                    "Default {} is outside the range {}",
+                    value, range
+                )));
+            }
+            log::warn!(
+                "Default {} is outside the range {}, clamped to {}",
+                value,
+                range,
+                clamped
+            );
+            *value = clamped;
+        }
+    }
+
+    let (values, enum_values, value_type) = match options.get::<Option<Value>>("values")? {
+        // `{ Physical = 0, Fire = 1 }` stores ints while the editor shows the labels
+        Some(Value::Table(t)) if t.raw_len() == 0 && !t.is_empty() => {
+            let mut labelled = Vec::new();
+            for pair in t.pairs::<String, i64>() {
+                let pair = pair.map_err(|e| {
+                    LuaError::RuntimeError(format!(
+                        "Enum values must map labels to integers: {}",
+                        e
+                    ))
+                })?;
+                labelled.push(pair);
+            }
+            labelled.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+            if let Some(pair) = labelled.windows(2).find(|pair| pair[0].1 == pair[1].1) {
+                return Err(LuaError::RuntimeError(format!(
+                    "Enum value {} is used by both {} and {}",
+                    pair[0].1, pair[0].0, pair[1].0
+                )));
+            }
+            let labels = labelled.iter().map(|(label, _)| label.clone()).collect();
+            (Some(labels), Some(labelled), None)
+        }
+        Some(Value::Table(t)) => {
+            let mut vec = Vec::new();
+            for value in t.sequence_values::<String>() {
+                if let Ok(v) = value {
+                    vec.push(v);
+                }
+            }
+            (Some(vec), None, None)
+        }
+        Some(Value::String(s)) => (None, None, s.to_str().ok().map(|s| s.to_string())),
+        _ => (None, None, None),
+    };
+
+    let keys = options.get::<Option<String>>("keys")?;
+    let item_type = options.get::<Option<String>>("item_type")?;
+
+    let schema = options.get::<Option<Table>>("schema")?.map(|t| {
+        let mut map = HashMap::new();
+        for pair in t.pairs::<String, String>() {
+            if let Ok((k, v)) = pair {
+                map.insert(k, v);
+            }
+        }
+        map
+    });
+
+    let nested_types = [
+        ("values".to_string(), value_type.as_ref()),
+        ("item_type".to_string(), item_type.as_ref()),
+        ("keys".to_string(), keys.as_ref()),
+    ];
+    let schema_types = schema
+        .iter()
+        .flatten()
+        .map(|(name, type_name)| (format!("schema entry {}", name), Some(type_name)));
+    for (option, type_name) in nested_types.into_iter().chain(schema_types) {
+        if let Some(type_name) = type_name
+            && !FIELD_TYPES.contains(&type_name.as_str())
+        {
+            return Err(LuaError::RuntimeError(format!(
+                "Unknown type {:?} for {}; expected one of {}",
+                type_name,
+                option,
+                FIELD_TYPES.join(", ")
+            )));
+        }
+    }
+
+    // Catches defaults left over from copy-pasting another field's definition
+    if field_type == "enum" {
+        let labels = values.as_deref().unwrap_or_default();
+        let is_member = match &default {
+            Some(DefaultValue::String(label)) => labels.contains(label),
+            Some(DefaultValue::Int(value)) => enum_values
+                .iter()
+                .flatten()
+                .any(|(_, stored)| stored == value),
+            _ => true,
+        };
+        if !is_member {
+            let default = match &default {
+                Some(DefaultValue::String(label)) => format!("{:?}", label),
+                Some(DefaultValue::Int(value)) => value.to_string(),
+                _ => String::new(),
+            };
+            return Err(LuaError::RuntimeError(format!(
+                "Enum default {} is not one of {}",
+                default,
+                labels.join(", ")
+            )));
+        }
+    }
+
+    if field_type == "map"
+        && let Some(keys) = &keys
+        && !MAP_KEY_TYPES.contains(&keys.as_str())
+    {
+        return Err(LuaError::RuntimeError(format!(
+            "Map keys must be one of {}, got {:?}",
+            MAP_KEY_TYPES.join(", "),
+            keys
+        )));
+    }
+
+    // Only an explicit flag makes a field required, so fields with defaults stay optional
+    let required = options.get::<Option<bool>>("required")?.unwrap_or(false);
+    let description = options.get::<Option<String>>("description")?;
+
+    Ok(FieldOptions {
+        default,
+        min,
+        max,
+        values,
+        enum_values,
+        keys,
+        value_type,
+        item_type,
+        schema,
+        required,
+        description,
+    })
+}
+
+pub fn create_lua_sandbox_and_island() -> (Lua, Island) {
+    create_lua_sandbox_with_limits(SandboxLimits::default())
+}
+
+/// Sandbox with the default instruction budget but a caller-chosen heap ceiling in bytes
+pub fn create_lua_sandbox_with_memory_limit(max_memory: usize) -> (Lua, Island) {
+    create_lua_sandbox_with_limits(SandboxLimits {
+        max_memory,
+        ..SandboxLimits::default()
+    })
+}
+
+/// Default sandbox whose `context` global holds `context`, so a script can branch on
+/// settings the game chooses, e.g. `context.difficulty`
+pub fn create_lua_sandbox_and_island_with_context(
+    context: HashMap<String, String>,
+) -> (Lua, Island) {
+    let (lua, island) = create_lua_sandbox_and_island();
+    lua.globals()
+        .set(
+            "context",
+            create_context_table(&lua, &context).expect("failed to create context"),
+        )
+        .expect("failed to set context global");
+    (lua, island)
+}
+
+pub fn create_lua_sandbox_with_limits(limits: SandboxLimits) -> (Lua, Island) {
+    let lua = Lua::new();
+    lua.sandbox(true).expect("failed to create sandbox");
+    lua.set_memory_limit(limits.max_memory)
+        .expect("failed to set memory limit");
+
+    let island = Island::new();
+    island.data.lock().unwrap().filesystem_access = limits.filesystem_access;
+    let instructions_used = Arc::clone(&island.instructions_used);
+    lua.set_interrupt(move |_lua| {
+        if instructions_used.fetch_add(1, Ordering::Relaxed) >= limits.max_instructions {
+            return Err(LuaError::RuntimeError(format!(
+                "Script exceeded its instruction budget of {}",
+                limits.max_instructions
+            )));
+        }
+        Ok(VmState::Continue)
+    });
+
+    lua.globals()
+        .set("island", island.clone())
+        .expect("failed to set island global");
+    lua.globals()
+        .set(
+            "print",
+            create_log_print(&lua, island.clone()).expect("failed to create print"),
+        )
+        .expect("failed to set print global");
+    lua.globals()
+        .set(
+            "context",
+            create_context_table(&lua, &HashMap::new()).expect("failed to create context"),
+        )
+        .expect("failed to set context global");
+
+    let island = Island {
+        lua: Some(Arc::new(lua.clone())),
+        ..island
+    };
+    (lua, island)
+}
+
+/// Run an island script from disk, naming the chunk after the file so errors read `island.luau:NN`
+pub fn exec_island_script(lua: &Lua, script_path: &Path) -> mlua::Result<()> {
+    let script = std::fs::read_to_string(script_path).map_err(IslandError::io(format!(
+        "Failed to read island script {}",
+        script_path.display()
+    )))?;
+    let chunk_name = script_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| script_path.display().to_string());
+    lua.load(&script)
+        .set_name(format!("@{}", chunk_name))
+        .exec()
+}
+
+/// Lint an island script: run it in a fresh sandbox rooted at `base_path`, then return the
+/// problems `island:validate()` finds. Script errors are returned as `Err` instead.
+/// Pass the `limits` the script will be loaded with, e.g. `UNTRUSTED` for community content.
+pub fn validate_island_script(
+    script: &str,
+    base_path: &Path,
+    limits: SandboxLimits,
+) -> mlua::Result<Vec<String>> {
+    let (lua, island) = create_lua_sandbox_with_limits(limits);
+    island.set_base_path(base_path.to_path_buf());
+    lua.load(script).set_name("@island.luau").exec()?;
+    island.finalize()?;
+    Ok(island.validate())
+}
+
+/// Read-only table of the settings passed in by the game; scripts can't change them
+fn create_context_table(lua: &Lua, context: &HashMap<String, String>) -> mlua::Result<Table> {
+    let table = lua.create_table_from(
+        context
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str())),
+    )?;
+    table.set_readonly(true);
+    Ok(table)
+}
+
+/// Replacement for Lua's print that forwards to the log crate, tagged with the island's content root
+fn create_log_print(lua: &Lua, island: Island) -> mlua::Result<Function> {
+    lua.create_function(move |lua, values: Variadic<Value>| {
+        let base_path = island.data.lock().unwrap().base_path.clone();
+        log::info!("{}", print_line(lua, &base_path, &values)?);
+        Ok(())
+    })
+}
+
+/// The line `print` logs: its values through `tostring`, tab-separated as in stock Lua
+fn print_line(lua: &Lua, base_path: &Path, values: &[Value]) -> mlua::Result<String> {
+    let tostring: Function = lua.globals().get("tostring")?;
+    let mut parts = Vec::with_capacity(values.len());
+    for value in values {
+        parts.push(tostring.call::<String>(value.clone())?);
+    }
+    Ok(format!("[{}] {}", base_path.display(), parts.join("\t")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_tile_layers() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            local layers = {"Background", "Floor", "Walls"}
+            island:set_tile_layers(layers)
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        assert_eq!(
+            island.get_tile_layers(),
+            vec!["Background", "Floor", "Walls"]
+        );
+    }
+
+    #[test]
+    fn test_set_entity_layers() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            local layers = {"Actors", "Triggers", "Items"}
+            island:set_entity_layers(layers)
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        assert_eq!(
+            island.get_entity_layers(),
+            vec!["Actors", "Triggers", "Items"]
+        );
+    }
+
+    #[test]
+    fn test_tile_layer_ordering_operations() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:set_tile_layers({"Floor", "Walls"})
+            island:add_tile_layer("Background", 1)
+            island:add_tile_layer("Decals")
+            island:move_tile_layer("Decals", 3)
+            island:remove_tile_layer("Walls")
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        assert_eq!(
+            island.get_tile_layers(),
+            vec!["Background", "Floor", "Decals"]
+        );
+    }
+
+    #[test]
+    fn test_entity_layer_ordering_operations() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:set_entity_layers({"Actors", "Triggers", "Items"})
+            island:move_entity_layer("Items", 1)
+            island:add_entity_layer("Effects", 2)
+            island:remove_entity_layer("Triggers")
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        assert_eq!(
+            island.get_entity_layers(),
+            vec!["Items", "Effects", "Actors"]
+        );
+    }
+
+    #[test]
+    fn test_layer_operations_reject_bad_names_and_indices() {
+        let (lua, island) = create_lua_sandbox_and_island();
+        lua.load(r#"island:set_tile_layers({"Floor", "Walls"})"#)
+            .exec()
+            .unwrap();
+
+        let cases = [
+            (
+                r#"island:add_tile_layer("Floor")"#,
+                "Tile layer Floor already exists",
+            ),
+            (
+                r#"island:add_tile_layer("Roof", 4)"#,
+                "Layer index 4 is out of range 1..=3",
+            ),
+            (
+                r#"island:add_tile_layer("Roof", 0)"#,
+                "Layer index 0 is out of range 1..=3",
+            ),
+            (
+                r#"island:remove_tile_layer("Roof")"#,
+                "Tile layer Roof does not exist",
+            ),
+            (
+                r#"island:move_tile_layer("Walls", 3)"#,
+                "Layer index 3 is out of range 1..=2",
+            ),
+            (
+                r#"island:move_entity_layer("Walls", 1)"#,
+                "Entity layer Walls does not exist",
+            ),
+        ];
+        for (script, expected) in cases {
+            let err = lua.load(script).exec().unwrap_err();
+            assert!(err.to_string().contains(expected), "{}: {}", script, err);
+        }
+
+        // Failed operations leave the list untouched
+        assert_eq!(island.get_tile_layers(), vec!["Floor", "Walls"]);
+    }
+
+    #[test]
+    fn test_register_tile_field_with_int() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_tile_field("lava_tile", "damage_on_touch", "int", { default = 10 })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .tile_fields
+            .get("lava_tile")
+            .expect("lava_tile not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "damage_on_touch");
+        assert_eq!(fields[0].field_type, "int");
+        assert!(fields[0].options.default.is_some());
+    }
+
+    #[test]
+    fn test_register_field_with_description() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_tile_field("lava_tile", "damage_on_touch", "int", { default = 10, description = "Damage dealt each turn an actor stands here" })
+            island:register_tile_field("lava_tile", "damage_type", "string", { default = "Fire" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        {
+            let data = island.data.lock().unwrap();
+            let fields = data.tile_fields.get("lava_tile").unwrap();
+            assert_eq!(
+                fields[0].options.description.as_deref(),
+                Some("Damage dealt each turn an actor stands here")
+            );
+            assert_eq!(fields[1].options.description, None);
+        }
+        lua.load(
+            r#"
+            local fields = island:get_tile_fields("lava_tile")
+            assert(fields[1].description == "Damage dealt each turn an actor stands here")
+            assert(fields[2].description == nil)
+        "#,
+        )
+        .exec()
+        .expect("description should be readable from Luau");
+    }
+
+    #[test]
+    fn test_get_tile_and_entity_types() {
+        let (lua, _island) = create_lua_sandbox_and_island();
+        let script = r#"
+            assert(#island:get_tile_types() == 0, "Nothing registered yet")
+            island:register_tile_field("water_tile", "depth", "int", {})
+            island:register_tile_field("lava_tile", "damage", "int", {})
+            island:register_tile_field("lava_tile", "glow", "bool", {})
+            island:register_entity_field("npc_basic", "health", "int", {})
+
+            local tile_types = island:get_tile_types()
+            assert(#tile_types == 2, "One entry per tile type")
+            assert(tile_types[1] == "lava_tile" and tile_types[2] == "water_tile", "Sorted")
+            local entity_types = island:get_entity_types()
+            assert(#entity_types == 1 and entity_types[1] == "npc_basic", "Entity types")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_enumeration_order_is_stable() {
+        use std::fs;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+        for name in [
+            "reed", "anchor", "mast", "hull", "bell", "oar", "keel", "sail",
+        ] {
+            fs::write(temp_dir.path().join(format!("{}.glb", name)), b"glTF").unwrap();
+        }
+
+        lua.load(
+            r#"
+            for _, name in { "reed", "anchor", "mast", "hull", "bell", "oar", "keel", "sail" } do
+                island:register_tile_field(name .. "_tile", "depth", "int", {})
+                island:register_entity_field(name .. "_npc", "health", "int", {})
+                island:register_gltf(name, name .. ".glb")
+            end
+            island:set_tile_layers({ "Water", "Floor", "Decals" })
+            island:add_entity_layer("Items")
+            island:add_entity_layer("Actors")
+        "#,
+        )
+        .exec()
+        .expect("Registration failed");
+
+        let snapshot = || -> Vec<Vec<String>> {
+            ["get_tile_types", "get_entity_types", "get_gltf_names"]
+                .iter()
+                .map(|accessor| {
+                    lua.load(format!("return island:{}()", accessor))
+                        .eval()
+                        .unwrap()
+                })
+                .collect()
+        };
+        let first = snapshot();
+        for names in &first {
+            assert!(names.is_sorted(), "{:?} should be sorted", names);
+            assert_eq!(names.len(), 8);
+        }
+        for _ in 0..5 {
+            assert_eq!(snapshot(), first);
+        }
+
+        // Layers keep the order they were given, not alphabetical order
+        assert_eq!(island.get_tile_layers(), vec!["Water", "Floor", "Decals"]);
+        assert_eq!(island.get_entity_layers(), vec!["Items", "Actors"]);
+    }
+
+    #[test]
+    fn test_register_tile_field_with_enum() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            local DamageType = {"Physical", "Fire", "Cold"}
+            island:register_tile_field("lava_tile", "damage_type", "enum", { values = DamageType, default = "Fire" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .tile_fields
+            .get("lava_tile")
+            .expect("lava_tile not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "damage_type");
+        assert_eq!(fields[0].field_type, "enum");
+        let values = fields[0].options.values.as_ref().expect("values not found");
+        assert_eq!(values, &vec!["Physical", "Fire", "Cold"]);
+    }
+
+    #[test]
+    fn test_register_tile_field_with_labelled_enum() {
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            local DamageType = { Physical = 0, Fire = 1, Cold = 5 }
+            island:register_tile_field("lava_tile", "damage_type", "enum", { values = DamageType, default = "Fire" })
+
+            local field = island:get_tile_fields("lava_tile")[1]
+            assert(field.values[1] == "Physical" and field.values[3] == "Cold", "Labels ordered by value")
+            assert(field.enum_values[2].label == "Fire", "Label exposed")
+            assert(field.enum_values[3].value == 5, "Stored value exposed")
+        "#;
+        lua.load(script).exec().expect("failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        let field = &data.tile_fields["lava_tile"][0];
+        let options = &field.options;
+        assert_eq!(
+            options.values.as_ref().unwrap(),
+            &vec!["Physical", "Fire", "Cold"]
+        );
+        assert_eq!(
+            options.enum_values.as_ref().unwrap(),
+            &vec![
+                ("Physical".to_string(), 0),
+                ("Fire".to_string(), 1),
+                ("Cold".to_string(), 5),
+            ]
+        );
+        assert_eq!(check_property_value(field, "5"), Ok(()));
+        assert_eq!(check_property_value(field, "Cold"), Ok(()));
+        assert!(check_property_value(field, "2").is_err());
+    }
+
+    #[test]
+    fn test_enum_default_must_be_a_value() {
+        let (lua, island) = create_lua_sandbox_and_island();
+
+        let err = lua
+            .load(r#"island:register_tile_field("lava_tile", "damage_type", "enum", { values = {"Physical", "Fire"}, default = "Cold" })"#)
+            .exec()
+            .expect_err("Default outside the values should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Enum default \"Cold\" is not one of Physical, Fire")
+        );
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "behavior", "enum", { values = { Idle = 0, Flee = 1 }, default = 7 })"#)
+            .exec()
+            .expect_err("Default outside the stored values should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Enum default 7 is not one of Idle, Flee")
+        );
+
+        lua.load(r#"island:register_entity_field("npc_basic", "behavior", "enum", { values = { Idle = 0, Flee = 1 }, default = 1 })"#)
+            .exec()
+            .expect("A stored value is a valid default");
+        let data = island.data.lock().unwrap();
+        assert!(data.tile_fields.is_empty());
+        assert_eq!(data.entity_fields["npc_basic"].len(), 1);
+    }
+
+    #[test]
+    fn test_labelled_enum_rejects_duplicate_values() {
+        let (lua, _island) = create_lua_sandbox_and_island();
+        let err = lua
+            .load(r#"island:register_tile_field("lava_tile", "damage_type", "enum", { values = { Fire = 1, Heat = 1 } })"#)
+            .exec()
+            .expect_err("Two labels sharing a value should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Enum value 1 is used by both Fire and Heat")
+        );
+    }
+
+    #[test]
+    fn test_register_tile_field_with_map() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_tile_field("teleport_tile", "destination", "map", { keys = "string", values = "int" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .tile_fields
+            .get("teleport_tile")
+            .expect("teleport_tile not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "destination");
+        assert_eq!(fields[0].field_type, "map");
+        assert_eq!(fields[0].options.keys, Some("string".to_string()));
+    }
+
+    #[test]
+    fn test_map_keys_restricted_to_string_or_int() {
+        let (lua, island) = create_lua_sandbox_and_island();
+
+        let err = lua
+            .load(r#"island:register_tile_field("teleport_tile", "destination", "map", { keys = "float" })"#)
+            .exec()
+            .expect_err("Float map keys should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Map keys must be one of string, int, got \"float\"")
+        );
+
+        lua.load(
+            r#"
+            island:register_tile_field("teleport_tile", "destination", "map", { keys = "string" })
+            island:register_tile_field("teleport_tile", "weights", "map", { keys = "int" })
+        "#,
+        )
+        .exec()
+        .expect("string and int keys should be accepted");
+
+        let data = island.data.lock().unwrap();
+        let fields = &data.tile_fields["teleport_tile"];
+        assert_eq!(check_property_value(&fields[0], r#"{"dock": 1}"#), Ok(()));
+        assert_eq!(
+            check_property_value(&fields[0], r#"{3: 1}"#),
+            Err("map key 3 is not string".to_string())
+        );
+        assert_eq!(check_property_value(&fields[1], r#"{3: 1.5}"#), Ok(()));
+        assert_eq!(
+            check_property_value(&fields[1], r#"{"three": 1.5}"#),
+            Err("map key \"three\" is not int".to_string())
+        );
+    }
+
+    #[test]
+    fn test_register_field_rejects_unknown_nested_types() {
+        let (lua, island) = create_lua_sandbox_and_island();
+
+        let cases = [
+            (
+                r#"{ keys = "string", values = "itn" }"#,
+                "Unknown type \"itn\" for values",
+            ),
+            (
+                r#"{ item_type = "strnig" }"#,
+                "Unknown type \"strnig\" for item_type",
+            ),
+            (
+                r#"{ keys = "uuid", values = "int" }"#,
+                "Unknown type \"uuid\" for keys",
+            ),
+            (
+                r#"{ schema = { hp = "int", mood = "feelings" } }"#,
+                "Unknown type \"feelings\" for schema entry mood",
+            ),
+        ];
+        for (options, expected) in cases {
+            let script = format!(
+                r#"island:register_entity_field("npc_basic", "stats", "map", {})"#,
+                options
+            );
+            let err = lua
+                .load(&script)
+                .exec()
+                .expect_err("Unknown nested type should be rejected");
+            assert!(err.to_string().contains(expected), "got {}", err);
+        }
+
+        lua.load(r#"island:register_entity_field("npc_basic", "stats", "map", { keys = "string", values = "int", schema = { hp = "int" } })"#)
+            .exec()
+            .expect("Known nested types should be accepted");
+        assert_eq!(
+            island
+                .data
+                .lock()
+                .unwrap()
+                .entity_fields
+                .get("npc_basic")
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_register_tile_field_with_list() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_tile_field("sign_tile", "messages", "list", { item_type = "string" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .tile_fields
+            .get("sign_tile")
+            .expect("sign_tile not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "messages");
+        assert_eq!(fields[0].field_type, "list");
+        assert_eq!(fields[0].options.item_type, Some("string".to_string()));
+    }
+
+    #[test]
+    fn test_register_list_and_map_defaults() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_tile_field("sign_tile", "messages", "list", { item_type = "string", default = { "a", "b" } })
+            island:register_entity_field("npc_basic", "stats", "map", { keys = "string", values = "int", default = { hp = 10 } })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        {
+            let data = island.data.lock().unwrap();
+            let messages = &data.tile_fields.get("sign_tile").unwrap()[0];
+            assert_eq!(
+                messages.options.default,
+                Some(DefaultValue::List(vec![
+                    DefaultValue::String("a".to_string()),
+                    DefaultValue::String("b".to_string()),
+                ]))
+            );
+            let stats = &data.entity_fields.get("npc_basic").unwrap()[0];
+            assert_eq!(
+                stats.options.default,
+                Some(DefaultValue::Map(HashMap::from([(
+                    "hp".to_string(),
+                    DefaultValue::Int(10)
+                )])))
+            );
+        }
+
+        let accessor_script = r#"
+            local messages = island:get_tile_fields("sign_tile")[1]
+            assert(messages.name == "messages" and messages.type == "list")
+            assert(messages.default[1] == "a" and messages.default[2] == "b")
+
+            local stats = island:get_entity_fields("npc_basic")[1]
+            assert(stats.default.hp == 10)
+            assert(#island:get_entity_fields("unknown") == 0)
+        "#;
+        lua.load(accessor_script)
+            .exec()
+            .expect("field metadata should be readable from Luau");
+    }
+
+    #[test]
+    fn test_non_scalar_list_and_map_defaults_are_rejected() {
+        let (lua, _island) = create_lua_sandbox_and_island();
+
+        let err = lua
+            .load(r#"island:register_tile_field("sign_tile", "messages", "list", { default = { "a", { "b" } } })"#)
+            .exec()
+            .expect_err("Nested list item should be rejected");
+        assert!(
+            err.to_string()
+                .contains("list default item 2 is a table, expected a string, number or boolean")
+        );
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "stats", "map", { default = { hp = { 10 } } })"#)
+            .exec()
+            .expect_err("Nested map value should be rejected");
+        assert!(err.to_string().contains(
+            "map default value for \"hp\" is a table, expected a string, number or boolean"
+        ));
+    }
+
+    #[test]
+    fn test_register_color_field() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r##"
+            island:register_tile_field("torch_tile", "tint", "color", { default = "#FF8000" })
+            island:register_tile_field("torch_tile", "glow", "color", { default = "#ff800080" })
+        "##;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        {
+            let data = island.data.lock().unwrap();
+            let fields = data.tile_fields.get("torch_tile").unwrap();
+            assert_eq!(
+                fields[0].options.default,
+                Some(DefaultValue::Color([255, 128, 0, 255]))
+            );
+            assert_eq!(
+                fields[1].options.default,
+                Some(DefaultValue::Color([255, 128, 0, 128]))
+            );
+        }
+        lua.load(r#"assert(island:get_tile_fields("torch_tile")[2].default.a == 128)"#)
+            .exec()
+            .expect("color channels should be readable from Luau");
+
+        for bad in ["FF8000", "#FF80", "#GG8000", "#FF800080FF"] {
+            let script = format!(
+                r#"island:register_tile_field("torch_tile", "bad", "color", {{ default = "{}" }})"#,
+                bad
+            );
+            let err = lua
+                .load(&script)
+                .exec()
+                .expect_err("Malformed color should be rejected");
+            assert!(err.to_string().contains("Invalid color default"), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_register_vector3_field() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_entity_field("npc_basic", "spawn_offset", "vector3", { default = { x = 1.5, y = 0, z = -2 } })
+            island:register_entity_field("npc_basic", "patrol_target", "vector3", { min = -10, max = 10, default = { x = 50, y = -50, z = 3 } })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+
+        // Assert
+        {
+            let data = island.data.lock().unwrap();
+            let fields = data.entity_fields.get("npc_basic").unwrap();
+            assert_eq!(
+                fields[0].options.default,
+                Some(DefaultValue::Vec3(1.5, 0.0, -2.0))
+            );
+            assert_eq!(
+                fields[1].options.default,
+                Some(DefaultValue::Vec3(10.0, -10.0, 3.0))
+            );
+        }
+        lua.load(
+            r#"
+            local target = island:get_entity_fields("npc_basic")[2].default
+            assert(target.x == 10 and target.y == -10 and target.z == 3)
+        "#,
+        )
+        .exec()
+        .expect("vector components should be readable from Luau");
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "bad", "vector3", { default = { x = 1 } })"#)
+            .exec()
+            .expect_err("Incomplete vector should be rejected");
+        assert!(err.to_string().contains("missing component y"));
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "bad", "vector3", { default = 5 })"#)
+            .exec()
+            .expect_err("Non-table vector should be rejected");
+        assert!(
+            err.to_string()
+                .contains("vector3 default must be a table of x, y and z, got integer")
+        );
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "bad", "vector3", { default = { x = 1, y = 2, z = 3, w = 4 } })"#)
+            .exec()
+            .expect_err("Vector with extra components should be rejected");
+        assert!(err.to_string().contains("exactly x, y and z"));
+    }
+
+    #[test]
+    fn test_int_default_outside_range() {
+        let (lua, island) = create_lua_sandbox_and_island();
+
+        let err = lua
+            .load(r#"island:register_entity_field("npc_basic", "level", "int", { min = 1, max = 10, default = 50 })"#)
+            .exec()
+            .expect_err("Out-of-range default should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Default 50 is outside the range 1..=10")
+        );
+
+        lua.load(r#"island:register_entity_field("npc_basic", "level", "int", { min = 1, max = 10, default = 50, clamp = true })"#)
+            .exec()
+            .expect("clamp should accept the default");
+        let data = island.data.lock().unwrap();
+        let level = &data.entity_fields["npc_basic"][0];
+        assert!(matches!(level.options.default, Some(DefaultValue::Int(10))));
+    }
+
+    #[test]
+    fn test_register_entity_field_with_int_range() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_entity_field("npc_basic", "health", "int", { min = 1, max = 1000, default = 100 })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .entity_fields
+            .get("npc_basic")
+            .expect("npc_basic not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "health");
+        assert_eq!(fields[0].field_type, "int");
+        assert_eq!(fields[0].options.min, Some(1));
+        assert_eq!(fields[0].options.max, Some(1000));
+    }
+
+    #[test]
+    fn test_register_entity_field_with_map_schema() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            island:register_entity_field("npc_basic", "stats", "map", { keys = "string", values = "int" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .entity_fields
+            .get("npc_basic")
+            .expect("npc_basic not found");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field_name, "stats");
+        assert_eq!(fields[0].field_type, "map");
+        assert_eq!(fields[0].options.keys, Some("string".to_string()));
+    }
+
+    #[test]
+    fn test_multiple_fields_on_same_entity() {
+        // Arrange
+        let (lua, island) = create_lua_sandbox_and_island();
+        let script = r#"
+            local AIBehavior = {"Idle", "Patrol", "Aggressive"}
+            island:register_entity_field("npc_basic", "health", "int", { default = 100 })
+            island:register_entity_field("npc_basic", "behavior", "enum", { values = AIBehavior, default = "Idle" })
+        "#;
+
+        // Act
+        lua.load(script).exec().expect("failed to execute script");
+        let data = island.data.lock().unwrap();
+
+        // Assert
+        let fields = data
+            .entity_fields
+            .get("npc_basic")
+            .expect("npc_basic not found");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].field_name, "health");
+        assert_eq!(fields[1].field_name, "behavior");
+    }
+
+    #[test]
+    fn test_load_island_config() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ron_dir = temp_dir.path().join("ron");
+        fs::create_dir(&ron_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        // Create island config file
+        let island_ron = r#"(
+            dock_room_id: 1,
+            name: "Test Island",
+            description: "A test island for loading",
+        )"#;
+
+        fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
+
+        // Create room_1.ron
+        let room_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_1.ron"), room_ron).unwrap();
+
+        let script = r#"
+            island:load_island_config("ron/island.ron")
+            island:register_room("ron/room_1.ron", {})
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        assert!(data.island_config.is_some());
+        assert_eq!(data.island_config.as_ref().unwrap().name, "Test Island");
+        assert_eq!(data.rooms.len(), 1, "Should have loaded registered room");
+    }
+
+    #[test]
+    fn test_load_island_config_merge() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let base_ron = r#"(
+            dock_room_id: 1,
+            name: "Base Island",
+            description: "The vanilla island",
+        )"#;
+        let overlay_ron = r#"(
+            name: "Modded Island",
+        )"#;
+        fs::write(temp_dir.path().join("island.ron"), base_ron).unwrap();
+        fs::write(temp_dir.path().join("overlay.ron"), overlay_ron).unwrap();
+
+        let err = lua
+            .load(r#"island:load_island_config("overlay.ron", { merge = true })"#)
+            .exec()
+            .expect_err("Merging without a base should fail");
+        assert!(err.to_string().contains("no base island config loaded"));
+
+        let script = r#"
+            island:load_island_config("island.ron")
+            island:load_island_config("overlay.ron", { merge = true })
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        let config = data.island_config.as_ref().unwrap();
+        assert_eq!(config.name, "Modded Island");
+        assert_eq!(config.dock_room_id, 1);
+        assert_eq!(config.description, "The vanilla island");
+    }
+
+    #[test]
+    fn test_load_island_config_manual_registration() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ron_dir = temp_dir.path().join("ron");
+        fs::create_dir(&ron_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        // Create island config file
+        let island_ron = r#"(
+            dock_room_id: 1,
+            name: "Test Island",
+            description: "A test island for loading",
+        )"#;
+        fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
+
+        // Create room_1.ron (dock room)
+        let room1_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_1.ron"), room1_ron).unwrap();
+
+        // Create room_2.ron (disconnected room)
+        let room2_ron = r#"(
+            room_id: 2,
+            pos_x: 10, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_2.ron"), room2_ron).unwrap();
+
+        let script = r#"
+            island:load_island_config("ron/island.ron")
+            island:register_room("ron/room_1.ron", {})
+            island:register_room("ron/room_2.ron", {})
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        assert_eq!(data.rooms.len(), 2, "Both rooms should be loaded via explicit registration");
+    }
+
+    #[test]
+    fn test_register_rooms_from_dir() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let rooms_dir = temp_dir.path().join("ron").join("rooms");
+        fs::create_dir_all(&rooms_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        for (room_id, pos_x) in [(1, 0), (2, 5), (3, 10)] {
+            let room_ron = format!(
+                r#"(
+                room_id: {},
+                pos_x: {}, pos_y: 0, pos_z: 0,
+                extent_x: 5, extent_y: 5, extent_z: 5,
+                looping_x: false, looping_y: false, looping_z: false,
+                tiles: {{}},
+            )"#,
+                room_id, pos_x
+            );
+            fs::write(rooms_dir.join(format!("room_{}.ron", room_id)), room_ron).unwrap();
+        }
+        fs::write(rooms_dir.join("notes.txt"), "not a room").unwrap();
+
+        let script = r#"
+            local count = island:register_rooms_from_dir("ron/rooms")
+            assert(count == 3, "Should load every room file")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+        assert_eq!(island.data.lock().unwrap().rooms.len(), 3);
+    }
+
+    #[test]
+    fn test_register_rooms_from_dir_collects_parse_errors() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let rooms_dir = temp_dir.path().join("rooms");
+        fs::create_dir_all(&rooms_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let room_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(rooms_dir.join("good.ron"), room_ron).unwrap();
+        fs::write(rooms_dir.join("broken_a.ron"), "(room_id: ").unwrap();
+        fs::write(rooms_dir.join("broken_b.ron"), "not ron at all").unwrap();
+
+        let err = lua
+            .load(r#"island:register_rooms_from_dir("rooms")"#)
+            .exec()
+            .expect_err("Broken room files should be reported");
+        let message = err.to_string();
+        assert!(message.contains("broken_a.ron"));
+        assert!(message.contains("broken_b.ron"));
+        assert!(!message.contains("good.ron"));
+        assert!(island.data.lock().unwrap().rooms.is_empty());
+    }
+
+    #[test]
+    fn test_register_rooms_from_dir_loads_nothing_on_overlap() {
+        use std::fs;
+
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        let rooms_dir = temp_dir.path().join("more_rooms");
+        fs::create_dir_all(&rooms_dir).unwrap();
+        let room_ids = || {
+            let data = island.data.lock().unwrap();
+            data.rooms
+                .iter()
+                .map(|room| room.room_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Room 3 is clear of everything; room 4 overlaps room 3, room 5 overlaps room 1
+        for (room_id, pos_x) in [(3, 20), (4, 22), (5, 2)] {
+            let room_ron = format!(
+                r#"(
+                room_id: {},
+                pos_x: {}, pos_y: 0, pos_z: 0,
+                extent_x: 5, extent_y: 5, extent_z: 5,
+                looping_x: false, looping_y: false, looping_z: false,
+                tiles: {{}},
+            )"#,
+                room_id, pos_x
+            );
+            fs::write(rooms_dir.join(format!("room_{}.ron", room_id)), room_ron).unwrap();
+        }
+
+        let err = lua
+            .load(r#"island:register_rooms_from_dir("more_rooms")"#)
+            .exec()
+            .expect_err("Overlapping rooms should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Room 4 overlaps room 3 loaded with it")
+        );
+        assert_eq!(room_ids(), vec![1, 2]);
+
+        fs::remove_file(rooms_dir.join("room_4.ron")).unwrap();
+        let err = lua
+            .load(r#"island:register_rooms_from_dir("more_rooms")"#)
+            .exec()
+            .expect_err("A room overlapping a registered one should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Room 5 overlaps already registered room 1")
+        );
+        assert_eq!(room_ids(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_loader_errors_keep_their_island_error_variant() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        std::fs::write(temp_dir.path().join("broken.ron"), "(room_id: ").unwrap();
+        // A directory passes the path checks but can't be read as a file
+        std::fs::create_dir(temp_dir.path().join("rooms")).unwrap();
+
+        let load = |script: &str| lua.load(script).exec().unwrap_err();
+
+        let err = load(r#"island:register_room("rooms", {})"#);
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::Io { .. })
+        ));
+        assert!(
+            err.to_string()
+                .contains("[io] Failed to read room file rooms")
+        );
+
+        let err = load(r#"island:register_room("broken.ron", {})"#);
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::RonParse {
+                kind: "room file",
+                ..
+            })
+        ));
+        assert!(
+            err.to_string()
+                .contains("[parse] Failed to parse room file broken.ron:1:")
+        );
+
+        let err = load(r#"island:register_room("../outside.ron", {})"#);
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::PathSecurity {
+                rejection: PathRejection::EscapesRoot,
+                ..
+            })
+        ));
+        assert!(
+            err.to_string()
+                .contains("[path] ../outside.ron escapes the content root")
+        );
+
+        let err = load(r#"island:register_room("rooms/missing.ron", {})"#);
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::PathSecurity {
+                rejection: PathRejection::NotFound,
+                ..
+            })
+        ));
+        assert!(
+            err.to_string()
+                .contains("[path] rooms/missing.ron not found under the content root")
+        );
+
+        let err = load(r#"island:register_room("room_1.ron", {})"#);
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::Validation(_))
+        ));
+        assert!(
+            err.to_string()
+                .contains("[validation] Room 1 overlaps already registered room 1")
+        );
+    }
+
+    #[test]
+    fn test_reload_room_swaps_room_in_place() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        let edited = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            tiles: {
+                10: Door(1, 2),
+                11: Tile(3),
+            },
+        )"#;
+        std::fs::write(temp_dir.path().join("room_1_edited.ron"), edited).unwrap();
+        let process = lua.create_function(|_, ()| Ok(())).unwrap();
+        let key = lua.create_registry_value(process).unwrap();
+        island.data.lock().unwrap().room_process_fns.insert(1, key);
+
+        lua.load(r#"island:reload_room(1, "room_1_edited.ron")"#)
+            .exec()
+            .expect("reload should succeed");
+
+        let data = island.data.lock().unwrap();
+        let ids: Vec<u32> = data.rooms.iter().map(|r| r.room_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(data.rooms[0].tiles.len(), 2);
+        assert!(data.room_process_fns.contains_key(&1));
+    }
+
+    #[test]
+    fn test_reload_room_rejects_mismatched_id() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+
+        let err = lua
+            .load(r#"island:reload_room(1, "room_2.ron")"#)
+            .exec()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Room file room_2.ron holds room 2, not room 1"),
+            "got {}",
+            err
+        );
+
+        let err = lua
+            .load(r#"island:reload_room(3, "room_3.ron")"#)
+            .exec()
+            .unwrap_err();
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::PathSecurity {
+                rejection: PathRejection::NotFound,
+                ..
+            })
+        ));
+        assert_eq!(island.data.lock().unwrap().rooms[0].tiles.len(), 1);
+    }
+
+    #[test]
+    fn test_escapes_root() {
+        assert!(!escapes_root(Path::new("rooms/room_1.ron")));
+        assert!(!escapes_root(Path::new("rooms/../room_1.ron")));
+        assert!(!escapes_root(Path::new("./room_1.ron")));
+        assert!(escapes_root(Path::new("../room_1.ron")));
+        assert!(escapes_root(Path::new("rooms/../../room_1.ron")));
+        assert!(escapes_root(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn test_register_room_rejects_overlap() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ron_dir = temp_dir.path().join("ron");
+        fs::create_dir(&ron_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let room1_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_1.ron"), room1_ron).unwrap();
+
+        // Shares a face with room 1
+        let room2_ron = r#"(
+            room_id: 2,
+            pos_x: 5, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_2.ron"), room2_ron).unwrap();
+
+        // Cuts into room 1
+        let room3_ron = r#"(
+            room_id: 3,
+            pos_x: 2, pos_y: 2, pos_z: 2,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(ron_dir.join("room_3.ron"), room3_ron).unwrap();
+
+        lua.load(
+            r#"
+            island:register_room("ron/room_1.ron", {})
+            island:register_room("ron/room_2.ron", {})
+        "#,
+        )
+        .exec()
+        .expect("Face-sharing rooms should register");
+
+        let err = lua
+            .load(r#"island:register_room("ron/room_3.ron", {})"#)
+            .exec()
+            .expect_err("Overlapping room should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Room 3 overlaps already registered room 1")
+        );
+        assert_eq!(island.data.lock().unwrap().rooms.len(), 2);
+    }
+
+    #[test]
+    fn test_load_entity_spawn() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ron_dir = temp_dir.path().join("ron").join("spawns");
+        fs::create_dir_all(&ron_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let spawn_ron = r#"(
+            entity_type: "npc_basic",
+            room_id: 1,
+            grid_index: 5,
+            properties: {
+                "health": "100",
+            },
+        )"#;
+
+        fs::write(ron_dir.join("enemy_1.ron"), spawn_ron).unwrap();
+
+        let script = r#"
+            island:load_entity_spawn("ron/spawns/enemy_1.ron")
+            assert(island:get_entity_spawn_count() == 1, "Entity spawn count should be 1")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        assert_eq!(data.entity_spawns.len(), 1);
+        assert_eq!(data.entity_spawns[0].entity_type, "npc_basic");
+    }
+
+    #[test]
+    fn test_get_entity_spawns_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let spawn_ron = r#"(
+            entity_type: "npc_basic",
+            room_id: 2,
+            grid_index: 7,
+            properties: {
+                "health": "100",
+                "mood": "calm",
+            },
+        )"#;
+        std::fs::write(temp_dir.path().join("npc.ron"), spawn_ron).unwrap();
+
+        let script = r#"
+            assert(#island:get_entity_spawns() == 0, "No spawns before loading")
+            island:load_entity_spawn("npc.ron")
+            local spawns = island:get_entity_spawns()
+            assert(#spawns == 1, "Expected one spawn")
+            local spawn = spawns[1]
+            assert(spawn.entity_type == "npc_basic", "wrong entity type")
+            assert(spawn.room_id == 2 and spawn.grid_index == 7, "wrong location")
+            assert(spawn.properties.health == "100", "wrong health")
+            assert(spawn.properties.mood == "calm", "wrong mood")
+            assert(spawn.rotation == nil and spawn.scale == nil, "No rotation or scale in file")
+        "#;
+        lua.load(script).exec().expect("get_entity_spawns failed");
+    }
+
+    #[test]
+    fn test_spawn_rotation_and_scale_reach_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let spawn_ron = r#"(
+            entity_type: "crate",
+            room_id: 1,
+            grid_index: 3,
+            properties: {},
+            rotation: Some(45.0),
+            scale: Some(2.0),
+        )"#;
+        std::fs::write(temp_dir.path().join("crate.ron"), spawn_ron).unwrap();
+
+        lua.load(
+            r#"
+            island:load_entity_spawn("crate.ron")
+            local spawn = island:get_entity_spawns()[1]
+            assert(spawn.rotation == 45, "rotation should be 45, got " .. tostring(spawn.rotation))
+            assert(spawn.scale == 2, "scale should be 2, got " .. tostring(spawn.scale))
+        "#,
+        )
+        .exec()
+        .expect("Spawn rotation and scale should be exposed");
+    }
+
+    #[test]
+    fn test_load_entity_spawns_from_dir() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let spawn_dir = temp_dir.path().join("spawns");
+        fs::create_dir_all(&spawn_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        for (file, entity_type) in [("b_chest.ron", "chest"), ("a_npc.ron", "npc_basic")] {
+            let spawn_ron = format!(
+                r#"(entity_type: "{}", room_id: 1, grid_index: 5, properties: {{}})"#,
+                entity_type
+            );
+            fs::write(spawn_dir.join(file), spawn_ron).unwrap();
+        }
+        fs::write(spawn_dir.join("notes.txt"), "ignored").unwrap();
+
+        let script = r#"
+            local count = island:load_entity_spawns_from_dir("spawns")
+            assert(count == 2, "Both spawn files should load")
+            assert(island:get_entity_spawn_count() == 2, "Spawns are registered")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        assert_eq!(data.entity_spawns[0].entity_type, "npc_basic");
+        assert_eq!(data.entity_spawns[1].entity_type, "chest");
+    }
+
+    #[test]
+    fn test_load_entity_spawns_from_dir_collects_parse_errors() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let spawn_dir = temp_dir.path().join("spawns");
+        fs::create_dir_all(&spawn_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        fs::write(
+            spawn_dir.join("good.ron"),
+            r#"(entity_type: "npc", room_id: 1, grid_index: 0, properties: {})"#,
+        )
+        .unwrap();
+        fs::write(spawn_dir.join("broken_a.ron"), "(entity_type: ").unwrap();
+        fs::write(spawn_dir.join("broken_b.ron"), "not ron at all").unwrap();
+
+        let err = lua
+            .load(r#"island:load_entity_spawns_from_dir("spawns")"#)
+            .exec()
+            .expect_err("Broken spawn files should be reported");
+        let message = err.to_string();
+        assert!(message.contains("broken_a.ron"));
+        assert!(message.contains("broken_b.ron"));
+        assert!(!message.contains("good.ron"));
+        assert!(island.data.lock().unwrap().entity_spawns.is_empty());
+    }
+
+    #[test]
+    fn test_validate_requires_registered_dock_room() {
+        let (temp_dir, _lua, _island) = load_adjacent_rooms_island();
+
+        let problems = validate_island_script(
+            r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_2.ron", {})
+            "#,
+            temp_dir.path(),
+            SandboxLimits::TRUSTED,
+        )
+        .expect("Script should run");
+        assert_eq!(problems, vec!["Dock room 1 is not registered"]);
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.set_base_path(temp_dir.path().to_path_buf());
+        lua.load(r#"island:load_island_config("island.ron")"#)
+            .exec()
+            .unwrap();
+        let err = lua.load("island:check_dock()").exec().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("[validation] Dock room 1 is not registered")
+        );
+
+        lua.load(r#"island:register_room("room_1.ron", {}) island:check_dock()"#)
+            .exec()
+            .expect("Dock is registered now");
+    }
+
+    #[test]
+    fn test_validate_warns_about_doors_on_looping_faces() {
+        let (temp_dir, _lua, _island) = load_adjacent_rooms_island();
+        let looping_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: true,
+            tiles: {
+                10: Door(1, 2),
+                6: Door(1, 2),
+            },
+        )"#;
+        std::fs::write(temp_dir.path().join("room_1_looping.ron"), looping_ron).unwrap();
+
+        let script = r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_1_looping.ron", {})
+            island:register_room("room_2.ron", {})
+        "#;
+        let problems = validate_island_script(script, temp_dir.path(), SandboxLimits::TRUSTED)
+            .expect("Script should run");
+        assert_eq!(
+            problems,
+            vec![
+                "Warning: door in room 1 at grid index 10 is on a looping face, \
+                 where wrapping leaves no other side"
+            ]
+        );
+
+        let problems = validate_island_script(
+            r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_1.ron", {})
+            island:register_room("room_2.ron", {})
+            "#,
+            temp_dir.path(),
+            SandboxLimits::TRUSTED,
+        )
+        .expect("Script should run");
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_validate_island_script() {
+        let (temp_dir, _lua, _island) = load_adjacent_rooms_island();
+
+        let problems = validate_island_script(
+            r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_1.ron", {})
+            "#,
+            temp_dir.path(),
+            SandboxLimits::TRUSTED,
+        )
+        .expect("Script should run");
+        assert_eq!(
+            problems,
+            vec!["Door in room 1 at grid index 10 targets missing room 2"]
+        );
+
+        let problems = validate_island_script(
+            r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_1.ron", {})
+            island:register_room("room_2.ron", {})
+            "#,
+            temp_dir.path(),
+            SandboxLimits::TRUSTED,
+        )
+        .expect("Script should run");
+        assert!(problems.is_empty());
+
+        let err =
+            validate_island_script(r#"error("boom")"#, temp_dir.path(), SandboxLimits::TRUSTED)
+                .expect_err("Script errors are returned, not listed as problems");
+        assert!(err.to_string().contains("island.luau:1: boom"));
+    }
+
+    #[test]
+    fn test_validate_island_script_applies_limits() {
+        let (temp_dir, _lua, _island) = load_adjacent_rooms_island();
+        let script = r#"island:register_room("room_1.ron", {})"#;
+
+        validate_island_script(script, temp_dir.path(), SandboxLimits::TRUSTED)
+            .expect("Trusted scripts may read files");
+        let err = validate_island_script(script, temp_dir.path(), SandboxLimits::UNTRUSTED)
+            .expect_err("Untrusted scripts may not read files");
+        assert!(
+            err.to_string()
+                .contains("filesystem access disabled in this sandbox")
+        );
+
+        let err = validate_island_script(
+            "while true do end",
+            temp_dir.path(),
+            SandboxLimits::UNTRUSTED,
+        )
+        .expect_err("Untrusted scripts are held to the untrusted instruction limit");
+        assert!(err.to_string().contains("instruction budget of 1000000"));
+    }
+
+    #[test]
+    fn test_validate_reports_missing_required_field() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let spawn_ron = r#"(
+            entity_type: "npc_basic",
+            room_id: 1,
+            grid_index: 5,
+            properties: {
+                "health": "100",
+            },
+        )"#;
+        fs::write(temp_dir.path().join("npc.ron"), spawn_ron).unwrap();
+
+        let script = r#"
+            island:register_entity_field("npc_basic", "health", "int", { default = 100 })
+            island:register_entity_field("npc_basic", "mood", "string", { default = "calm" })
+            island:register_entity_field("npc_basic", "dialogue_id", "string", { required = true })
+            island:load_entity_spawn("npc.ron")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let problems = island.validate();
+        let missing: Vec<&String> = problems
+            .iter()
+            .filter(|p| p.contains("missing required field"))
+            .collect();
+        assert_eq!(
+            missing,
+            vec!["Spawn npc_basic in room 1 at grid index 5 is missing required field dialogue_id"]
+        );
+    }
+
+    #[test]
+    fn test_validate_spawns_checks_property_types() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let good_spawn = r#"(
+            entity_type: "npc_basic",
+            room_id: 1,
+            grid_index: 5,
+            properties: {
+                "health": "100",
+                "behavior": "Patrol",
+                "tags": "[\"guard\"]",
+            },
+        )"#;
+        let bad_spawn = r#"(
+            entity_type: "npc_basic",
+            room_id: 2,
+            grid_index: 7,
+            properties: {
+                "health": "lots",
+                "behavior": "Sleepy",
+                "hat": "fedora",
+            },
+        )"#;
+        fs::write(temp_dir.path().join("good.ron"), good_spawn).unwrap();
+        fs::write(temp_dir.path().join("bad.ron"), bad_spawn).unwrap();
+
+        let script = r#"
+            local AIBehavior = {"Idle", "Patrol", "Aggressive"}
+            island:register_entity_field("npc_basic", "health", "int", { min = 1, max = 1000, default = 100 })
+            island:register_entity_field("npc_basic", "behavior", "enum", { values = AIBehavior, default = "Idle" })
+            island:register_entity_field("npc_basic", "tags", "list", { item_type = "string" })
+            island:load_entity_spawn("good.ron")
+            island:load_entity_spawn("bad.ron")
+            return island:validate_spawns()
+        "#;
+        let problems: Vec<String> = lua.load(script).eval().expect("Failed to execute script");
+
+        assert_eq!(
+            problems,
+            vec![
+                "Spawn npc_basic in room 2 at grid index 7 property behavior: \"Sleepy\" is not one of Idle, Patrol, Aggressive",
+                "Spawn npc_basic in room 2 at grid index 7 has unknown property hat",
+                "Spawn npc_basic in room 2 at grid index 7 property health: expected int, got \"lots\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_spawns_resolves_entity_refs() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let spawns = [
+            (
+                "post.ron",
+                r#"(id: Some("post_a"), entity_type: "patrol_point", room_id: 2, grid_index: 3, properties: {})"#,
+            ),
+            (
+                "guard.ron",
+                r#"(entity_type: "guard", room_id: 1, grid_index: 4, properties: { "post": "post_a" })"#,
+            ),
+            (
+                "lost.ron",
+                r#"(entity_type: "guard", room_id: 1, grid_index: 6, properties: { "post": "post_b" })"#,
+            ),
+            (
+                "copy.ron",
+                r#"(id: Some("post_a"), entity_type: "patrol_point", room_id: 2, grid_index: 8, properties: {})"#,
+            ),
+        ];
+        for (file, ron) in spawns {
+            std::fs::write(temp_dir.path().join(file), ron).unwrap();
+        }
+
+        let problems: Vec<String> = lua
+            .load(
+                r#"
+                island:register_entity_field("guard", "post", "entity_ref", {})
+                for _, file in { "post.ron", "guard.ron", "lost.ron", "copy.ron" } do
+                    island:load_entity_spawn(file)
+                end
+                assert(island:get_entity_spawns()[1].id == "post_a", "Spawn ids reach Luau")
+                return island:validate_spawns()
+            "#,
+            )
+            .eval()
+            .expect("validate_spawns failed");
+
+        assert_eq!(
+            problems,
+            vec![
+                "Spawn patrol_point in room 2 at grid index 8 reuses id \"post_a\" from Spawn patrol_point in room 2 at grid index 3",
+                "Spawn guard in room 1 at grid index 6 property post points at missing spawn id \"post_b\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_register_gltf() {
+        use std::fs;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("models")).unwrap();
+        fs::write(temp_dir.path().join("models/character.gltf"), "{}").unwrap();
+        fs::write(temp_dir.path().join("models/tree.glb"), b"glTF").unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let script = r#"
+            island:register_gltf("character", "models/character.gltf")
+            island:register_gltf("tree", "models/tree.glb")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let data = island.data.lock().unwrap();
+        assert_eq!(data.gltf_registry.len(), 2);
+        assert!(data.gltf_registry.contains_key("character"));
+        assert!(data.gltf_registry.contains_key("tree"));
+    }
+
+    #[test]
+    fn test_get_gltf_names_from_luau() {
+        use std::fs;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("models")).unwrap();
+        fs::write(temp_dir.path().join("models/character.gltf"), "{}").unwrap();
+        fs::write(temp_dir.path().join("models/tree.glb"), b"glTF").unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let script = r#"
+            assert(#island:get_gltf_names() == 0, "Nothing registered yet")
+            island:register_gltf("tree", "models/tree.glb")
+            island:register_gltf("character", "models/character.gltf")
+
+            local names = island:get_gltf_names()
+            assert(#names == 2, "Should list both models")
+            assert(names[1] == "character" and names[2] == "tree", "Names should be sorted")
+            assert(island:has_gltf("tree"), "tree should be registered")
+            assert(not island:has_gltf("rock"), "rock should not be registered")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_register_tile_palette() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        std::fs::write(temp_dir.path().join("grass.glb"), b"glTF").unwrap();
+
+        let script = r#"
+            island:register_gltf("grass", "grass.glb")
+            island:register_tile_palette(3, "grass")
+            assert(island:get_tile_model(3) == "grass", "Palette 3 draws grass")
+            assert(island:get_tile_model(4) == nil, "Palette 4 has no model")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+
+        let err = lua
+            .load(r#"island:register_tile_palette(4, "stone")"#)
+            .exec()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("[validation] Tile palette 4 uses unregistered GLTF model stone"),
+            "{}",
+            err
+        );
+        assert_eq!(
+            island.data.lock().unwrap().tile_palette,
+            HashMap::from([(3, "grass".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_register_gltf_missing_file() {
+        use std::fs;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("models")).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let err = lua
+            .load(r#"island:register_gltf("character", "models/charcter.gltf")"#)
+            .exec()
+            .expect_err("Missing GLTF file should be rejected");
+        assert!(
+            err.to_string()
+                .contains("GLTF file for character not found: models/charcter.gltf")
+        );
+        assert!(island.data.lock().unwrap().gltf_registry.is_empty());
+    }
+
+    #[test]
+    fn test_register_gltf_rejects_non_model_extension() {
+        use std::fs;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("models")).unwrap();
+        fs::write(temp_dir.path().join("models/notes.txt"), "not a model").unwrap();
+        fs::write(temp_dir.path().join("models/ROCK.GLB"), b"glTF").unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let err = lua
+            .load(r#"island:register_gltf("notes", "models/notes.txt")"#)
+            .exec()
+            .expect_err("Non-model file should be rejected");
+        assert!(
+            err.to_string()
+                .contains("GLTF file for notes must end in .gltf or .glb: models/notes.txt")
+        );
+
+        lua.load(r#"island:register_gltf("rock", "models/ROCK.GLB")"#)
+            .exec()
+            .expect("Extension check should ignore case");
+        let data = island.data.lock().unwrap();
+        assert!(!data.gltf_registry.contains_key("notes"));
+        assert!(data.gltf_registry.contains_key("rock"));
+    }
+
+    #[test]
+    fn test_rooms_are_adjacent_from_luau() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ron_dir = temp_dir.path().join("ron");
+        fs::create_dir(&ron_dir).unwrap();
+
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        // Create island config
+        let island_ron = r#"(
+            dock_room_id: 1,
+            name: "Test",
+            description: "Test",
+        )"#;
+        fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
+
+        // Create two adjacent rooms with door connection
+        let room1_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {
+                10: Door(1, 2),
+            },
+        )"#;
+
+        let room2_ron = r#"(
+            room_id: 2,
+            pos_x: 5, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+
+        fs::write(ron_dir.join("room_1.ron"), room1_ron).unwrap();
+        fs::write(ron_dir.join("room_2.ron"), room2_ron).unwrap();
+
+        let script = r#"
+            island:load_island_config("ron/island.ron")
+            island:register_room("ron/room_1.ron", {})
+            island:register_room("ron/room_2.ron", {})
+
+            local adjacent = island:rooms_are_adjacent(1, 2)
+            assert(adjacent == true, "Rooms should be adjacent")
+
+            local not_adjacent = island:rooms_are_adjacent(1, 999)
+            assert(not_adjacent == false, "Non-existent room should not be adjacent")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_adjacency_reason_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let script = r#"
+            assert(island:adjacency_reason(1, 2) == "adjacent", "Touching rooms")
+            assert(island:adjacency_reason(1, 999) == "room_missing:999", "Missing room is named")
+            assert(island:adjacency_reason(2, 2) == "same_room", "A room isn't adjacent to itself")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_unpaired_doors_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let script = r#"
+            local unpaired = island:unpaired_doors()
+            assert(#unpaired == 1, "Room 2 has no door back to room 1")
+            assert(unpaired[1].from == 1 and unpaired[1].to == 2, "Unpaired door should be 1 -> 2")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_get_doors_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+
+        let script = r#"
+            local doors = island:get_doors()
+            assert(#doors == 1, "Should have one door")
+            assert(doors[1].from == 1 and doors[1].to == 2, "Door should connect room 1 to room 2")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_door_palette_is_reported() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let room2_ron = r#"(
+            room_id: 2,
+            pos_x: 5, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            tiles: {
+                4: Tile(3, North),
+                7: Door(42, 1),
+            },
+        )"#;
+        std::fs::write(temp_dir.path().join("room_2_doors.ron"), room2_ron).unwrap();
+
+        let script = r#"
+            island:reload_room(2, "room_2_doors.ron")
+
+            local doors = island:get_doors()
+            assert(#doors == 2, "Both rooms have a door")
+            assert(doors[1].from == 1 and doors[1].palette == 1, "Room 1 door keeps palette 1")
+            assert(doors[2].from == 2 and doors[2].to == 1, "Room 2 door leads back")
+            assert(doors[2].palette == 42, "Room 2 door reports palette 42")
+
+            local room_doors = island:get_room_doors(2)
+            assert(#room_doors == 1, "The plain tile isn't a door")
+            assert(room_doors[1].index == 7, "Door sits at grid index 7")
+            assert(room_doors[1].to == 1, "Door leads to room 1")
+            assert(room_doors[1].palette == 42, "Door uses palette 42")
+
+            assert(island:get_room_doors(999) == nil, "Unknown rooms have no doors")
+        "#;
+        lua.load(script).exec().expect("door palette script failed");
+    }
+
+    /// Island with room 1 at the origin and room 2 beside it on +x, both 5x5x5,
+    /// and a door in room 1 leading to room 2
+    fn load_adjacent_rooms_island() -> (tempfile::TempDir, Lua, Island) {
+        use std::fs;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.set_base_path(temp_dir.path().to_path_buf());
+
+        let island_ron = r#"(
+            dock_room_id: 1,
+            name: "Test",
+            description: "Test",
+        )"#;
+        let room1_ron = r#"(
+            room_id: 1,
+            pos_x: 0, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {
+                10: Door(1, 2),
+            },
+        )"#;
+        let room2_ron = r#"(
+            room_id: 2,
+            pos_x: 5, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_x: false, looping_y: false, looping_z: false,
+            tiles: {},
+        )"#;
+        fs::write(temp_dir.path().join("island.ron"), island_ron).unwrap();
+        fs::write(temp_dir.path().join("room_1.ron"), room1_ron).unwrap();
+        fs::write(temp_dir.path().join("room_2.ron"), room2_ron).unwrap();
 
-    let schema = options.get::<Option<Table>>("schema")?.map(|t| {
-        let mut map = HashMap::new();
-        for pair in t.pairs::<String, String>() {
-            if let Ok((k, v)) = pair {
-                map.insert(k, v);
-            }
-        }
-        map
-    });
+        let script = r#"
+            island:load_island_config("island.ron")
+            island:register_room("room_1.ron", {})
+            island:register_room("room_2.ron", {})
+        "#;
+        lua.load(script).exec().expect("Failed to load test island");
 
-    Ok(FieldOptions {
-        default,
-        min,
-        max,
-        values,
-        keys,
-        value_type,
-        item_type,
-        schema,
-    })
-}
+        (temp_dir, lua, island)
+    }
 
-pub fn create_lua_sandbox_and_island() -> (Lua, Island) {
-    let lua = Lua::new();
-    lua.sandbox(true).expect("failed to create sandbox");
+    #[test]
+    fn test_for_each_world_tile_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
 
-    let island = Island::new();
-    lua.globals()
-        .set("island", island.clone())
-        .expect("failed to set island global");
+        let script = r#"
+            local seen = {}
+            island:for_each_world_tile(function(x, y, z, tile)
+                table.insert(seen, {x, y, z, tile.kind, tile.palette, tile.to_room})
+            end)
+            assert(#seen == 1, "expected the one door tile, got " .. #seen)
+            local door = seen[1]
+            assert(door[1] == 0 and door[2] == 2 and door[3] == 0, "door at wrong cell")
+            assert(door[4] == "door" and door[5] == 1 and door[6] == 2, "door decoded wrong")
+        "#;
+        lua.load(script).exec().expect("for_each_world_tile failed");
+    }
 
-    (lua, island)
-}
+    #[test]
+    fn test_get_rooms_with_tag_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let tagged = r#"(
+            room_id: 3,
+            pos_x: 10, pos_y: 0, pos_z: 0,
+            tiles: {},
+            tags: ["forest", "boss"],
+        )"#;
+        std::fs::write(temp_dir.path().join("room_3.ron"), tagged).unwrap();
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        let script = r#"
+            island:register_room("room_3.ron", {})
+            local boss = island:get_rooms_with_tag("boss")
+            assert(#boss == 1 and boss[1] == 3, "expected room 3 tagged boss")
+            assert(#island:get_rooms_with_tag("tutorial") == 0, "nothing is tagged tutorial")
+        "#;
+        lua.load(script).exec().expect("get_rooms_with_tag failed");
+    }
 
     #[test]
-    fn test_set_tile_layers() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_room_at_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+
         let script = r#"
-            local layers = {"Background", "Floor", "Walls"}
-            island:set_tile_layers(layers)
+            assert(island:room_at(1, 1, 1) == 1, "Point inside room 1")
+            assert(island:room_at(7, 2, 2) == 2, "Point inside room 2")
+            assert(island:room_at(5, 2, 2) == 1, "Shared face resolves to the lower room")
+            assert(island:room_at(50, 0, 0) == nil, "Point outside every room")
         "#;
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
+        lua.load(script).exec().expect("Failed to execute script");
+    }
 
-        // Assert
-        assert_eq!(
-            island.get_tile_layers(),
-            vec!["Background", "Floor", "Walls"]
-        );
+    #[test]
+    fn test_shared_face_area_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+
+        let script = r#"
+            assert(island:shared_face_area(1, 2) == 25, "Rooms share a full 5x5 face")
+            assert(island:shared_face_area(2, 1) == 25, "Area is symmetric")
+            assert(island:shared_face_area(1, 999) == 0, "Unknown room shares nothing")
+
+            island:register_room_table({
+                room_id = 3,
+                pos = { x = 10, y = 0, z = 0 },
+                extent = { x = 5, y = 5, z = 5 },
+            }, {})
+            assert(island:shared_face_area(1, 3) == 0, "Room 2 sits between rooms 1 and 3")
+            island:set_world_wrap({ x = 15 })
+            assert(island:shared_face_area(1, 3) == 25, "Rooms 1 and 3 meet across the seam")
+            assert(island:shared_face_area(3, 1) == 25, "Area across the seam is symmetric")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_set_entity_layers() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_tile_neighbors_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+
         let script = r#"
-            local layers = {"Actors", "Triggers", "Items"}
-            island:set_entity_layers(layers)
+            local neighbors = island:tile_neighbors(1, 0)
+            assert(#neighbors == 3, "Corner tile has three neighbors")
+            assert(neighbors[1] == 1 and neighbors[2] == 5 and neighbors[3] == 25, "Sorted x, y, z neighbors")
+            assert(island:tile_neighbors(999, 0) == nil, "Unknown room")
         "#;
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
+        lua.load(script).exec().expect("Failed to execute script");
+    }
 
-        // Assert
+    #[test]
+    fn test_load_island_binary_matches_ron_loaded_island() {
+        let (temp_dir, _lua, island) = load_adjacent_rooms_island();
+        let baked = island.get_mechanics_island_data().unwrap().to_bincode();
+        std::fs::write(temp_dir.path().join("island.bin"), &baked).unwrap();
+        std::fs::write(
+            temp_dir.path().join("broken.bin"),
+            &baked[..baked.len() / 2],
+        )
+        .unwrap();
+
+        let (lua, binary_island) = create_lua_sandbox_and_island();
+        binary_island.set_base_path(temp_dir.path().to_path_buf());
+        lua.load(
+            r#"
+            island:load_island_binary("island.bin")
+            assert(island:get_dock_room_id() == 1, "Dock from the baked config")
+            assert(island:get_room_count() == 2, "Both rooms were baked")
+            local doors = island:get_doors()
+            assert(#doors == 1 and doors[1].to == 2, "Door survives baking")
+            assert(island:rooms_are_adjacent(1, 2), "Adjacency survives baking")
+        "#,
+        )
+        .exec()
+        .expect("Failed to load binary island");
         assert_eq!(
-            island.get_entity_layers(),
-            vec!["Actors", "Triggers", "Items"]
+            binary_island.get_mechanics_island_data().unwrap().to_json(),
+            island.get_mechanics_island_data().unwrap().to_json()
         );
+
+        let err = lua
+            .load(r#"island:load_island_binary("broken.bin")"#)
+            .exec()
+            .unwrap_err();
+        assert!(matches!(
+            IslandError::from_lua_error(&err),
+            Some(IslandError::BinaryParse { .. })
+        ));
+        assert!(err.to_string().contains("[parse]"), "{}", err);
+        let err = lua
+            .load(r#"island:load_island_binary("island.ron")"#)
+            .exec()
+            .unwrap_err();
+        assert!(err.to_string().contains("not a binary island"), "{}", err);
     }
 
     #[test]
-    fn test_register_tile_field_with_int() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_get_adjacency_matrix_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            local matrix = island:get_adjacency_matrix()
+            assert(#matrix[1] == 1 and matrix[1][1] == 2, "Room 1 touches room 2")
+            assert(#matrix[2] == 1 and matrix[2][1] == 1, "Room 2 touches room 1")
+            assert(matrix[3] == nil, "Only registered rooms are keyed")
+        "#,
+        )
+        .exec()
+        .expect("get_adjacency_matrix failed");
+    }
+
+    #[test]
+    fn test_get_room_clusters_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let far_room_ron = r#"(
+            room_id: 7,
+            pos_x: 50, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+        )"#;
+        std::fs::write(temp_dir.path().join("room_7.ron"), far_room_ron).unwrap();
+
         let script = r#"
-            island:register_tile_field("lava_tile", "damage_on_touch", "int", { default = 10 })
+            local clusters = island:get_room_clusters()
+            assert(#clusters == 1, "Rooms 1 and 2 form one island")
+            assert(#clusters[1] == 2 and clusters[1][1] == 1 and clusters[1][2] == 2, "Sorted ids")
+
+            island:register_room("room_7.ron", {})
+            clusters = island:get_room_clusters()
+            assert(#clusters == 2, "Room 7 split off")
+            assert(#clusters[2] == 1 and clusters[2][1] == 7, "Room 7 is on its own")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
-        let data = island.data.lock().unwrap();
+    #[test]
+    fn test_find_path_detailed_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let room3_ron = r#"(
+            room_id: 3,
+            pos_x: 10, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+        )"#;
+        std::fs::write(temp_dir.path().join("room_3.ron"), room3_ron).unwrap();
 
-        // Assert
-        let fields = data
-            .tile_fields
-            .get("lava_tile")
-            .expect("lava_tile not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "damage_on_touch");
-        assert_eq!(fields[0].field_type, "int");
-        assert!(fields[0].options.default.is_some());
+        let script = r#"
+            island:register_room("room_3.ron", {})
+
+            local path = island:find_path_detailed(1, 3)
+            assert(#path == 2, "Start room isn't listed")
+            assert(path[1].room_id == 2 and path[1].via == "door", "Room 1 opens its door")
+            assert(path[1].palette == 1 and path[1].face == nil, "Doors report their palette")
+            assert(path[2].room_id == 3 and path[2].via == "adjacent", "Then walks on")
+            assert(path[2].face == "+x" and path[2].palette == nil, "Across room 2's +x face")
+
+            local back = island:find_path_detailed(3, 1)
+            assert(back[2].via == "adjacent" and back[2].face == "-x", "No door on the way back")
+
+            assert(#island:find_path_detailed(2, 2) == 0, "Already there")
+            assert(island:find_path_detailed(1, 99) == nil, "Unknown rooms have no path")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_register_tile_field_with_enum() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_find_path_weighted_from_luau() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        island.data.lock().unwrap().rooms_mut()[1]
+            .tiles
+            .insert(0, TileData::Tile(3, Default::default()));
+
         let script = r#"
-            local DamageType = {"Physical", "Fire", "Cold"}
-            island:register_tile_field("lava_tile", "damage_type", "enum", { values = DamageType, default = "Fire" })
-        "#;
+            local path = island:find_path_weighted(1, 0, 2, 0)
+            assert(path.cost == 5, "Five unit steps from (0, 0, 0) to (5, 0, 0)")
+            assert(#path.steps == 6, "Steps include the start tile")
+            assert(path.steps[1].room == 1 and path.steps[1].index == 0, "Starts at the origin")
+            assert(path.steps[6].room == 2 and path.steps[6].index == 0, "Ends in room 2")
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
-        let data = island.data.lock().unwrap();
+            island:set_tile_cost(3, 10)
+            assert(island:find_path_weighted(1, 0, 2, 0).cost == 14, "Entering the target tile costs 10")
 
-        // Assert
-        let fields = data
-            .tile_fields
-            .get("lava_tile")
-            .expect("lava_tile not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "damage_type");
-        assert_eq!(fields[0].field_type, "enum");
-        let values = fields[0].options.values.as_ref().expect("values not found");
-        assert_eq!(values, &vec!["Physical", "Fire", "Cold"]);
+            island:set_tile_cost(3, -1)
+            assert(island:find_path_weighted(1, 0, 2, 0) == nil, "Impassable target tile")
+            assert(island:find_path_weighted(1, 0, 2, 1) ~= nil, "Neighboring tile still reachable")
+            assert(not pcall(function() island:set_tile_cost(3, 0 / 0) end), "NaN cost rejected")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_register_tile_field_with_map() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_clear_resets_registered_state() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        std::fs::write(temp_dir.path().join("tree.glb"), b"glTF").unwrap();
+
         let script = r#"
-            island:register_tile_field("teleport_tile", "destination", "map", { keys = "string", values = "int" })
+            island:set_tile_layers({"ground"})
+            island:set_entity_layers({"npcs"})
+            island:register_tile_field("grass", "height", "int", {})
+            island:register_entity_field("npc", "name", "string", {})
+            island:register_gltf("tree", "tree.glb")
+            island:set_tile_cost(1, 3)
+            island:register_process_fn(function(delta) end)
+            island:register_physics_process_fn(function(delta) end)
+            assert(island:get_room_count() == 2, "Rooms registered before clearing")
+
+            island:clear()
+            assert(island:get_room_count() == 0, "No rooms after clearing")
+            assert(#island:get_gltf_names() == 0, "No models after clearing")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
         let data = island.data.lock().unwrap();
+        assert!(data.tile_layers.is_empty());
+        assert!(data.entity_layers.is_empty());
+        assert!(data.tile_fields.is_empty());
+        assert!(data.entity_fields.is_empty());
+        assert!(data.island_config.is_none());
+        assert!(data.rooms.is_empty());
+        assert!(data.entity_spawns.is_empty());
+        assert!(data.gltf_registry.is_empty());
+        assert!(data.tile_palette.is_empty());
+        assert!(data.tile_costs.is_empty());
+        assert!(data.room_process_fns.is_empty());
+        assert!(data.room_physics_process_fns.is_empty());
+        assert!(data.process_fn.is_none());
+        assert!(data.physics_process_fn.is_none());
+        assert_eq!(data.base_path, temp_dir.path());
+    }
 
-        // Assert
-        let fields = data
-            .tile_fields
-            .get("teleport_tile")
-            .expect("teleport_tile not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "destination");
-        assert_eq!(fields[0].field_type, "map");
-        assert_eq!(fields[0].options.keys, Some("string".to_string()));
+    #[test]
+    fn test_mechanics_island_data_is_cached_until_rooms_change() {
+        let (_temp_dir, _lua, island) = load_adjacent_rooms_island();
+        let first = island.get_mechanics_island_data().unwrap();
+        assert!(Arc::ptr_eq(
+            &first,
+            &island.get_mechanics_island_data().unwrap()
+        ));
+        assert!(first.rooms_are_adjacent(1, 2));
+
+        island.data.lock().unwrap().rooms_mut()[1].pos_x = 15;
+        let moved = island.get_mechanics_island_data().unwrap();
+        assert!(!Arc::ptr_eq(&first, &moved));
+        assert!(!moved.rooms_are_adjacent(1, 2));
     }
 
     #[test]
-    fn test_register_tile_field_with_list() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_set_world_wrap_from_luau() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        island.data.lock().unwrap().rooms_mut()[1].pos_x = 15;
+
         let script = r#"
-            island:register_tile_field("sign_tile", "messages", "list", { item_type = "string" })
+            assert(not island:rooms_are_adjacent(1, 2), "Rooms at opposite ends")
+            island:set_world_wrap({ x = 20 })
+            assert(island:rooms_are_adjacent(1, 2), "Rooms meet across the x seam")
+            assert(not pcall(function() island:set_world_wrap({ y = 0 }) end), "Sizes must be positive")
+            island:set_world_wrap({})
+            assert(not island:rooms_are_adjacent(1, 2), "Empty table turns wrapping off")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_assertion_helpers_name_what_is_missing() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            island:register_entity_field("npc", "health", "int", {})
+            island:register_entity_field("npc", "name", "string", {})
+            island:assert_room_exists(1)
+            island:assert_entity_field("npc", "health")
+            island:assert_adjacent(1, 2)
+        "#,
+        )
+        .exec()
+        .expect("Passing assertions should not raise");
+
+        let failure = |script: &str| lua.load(script).exec().unwrap_err().to_string();
+        assert!(
+            failure("island:assert_room_exists(7)")
+                .contains("Assertion failed: room 7 is not registered (registered rooms: 1, 2)")
+        );
+        assert!(
+            failure(r#"island:assert_entity_field("npc", "hat")"#)
+                .contains("entity type npc has no field hat (fields: health, name)")
+        );
+        assert!(
+            failure(r#"island:assert_entity_field("boat", "speed")"#)
+                .contains("entity type boat has no registered fields")
+        );
+        assert!(
+            failure("island:assert_adjacent(1, 9)")
+                .contains("rooms 1 and 9 are not adjacent, room 9 is not registered")
+        );
+        assert!(
+            failure("island:assert_adjacent(2, 2)")
+                .contains("rooms 2 and 2 are not adjacent, they are the same room")
+        );
+    }
+
+    #[test]
+    fn test_register_room_table() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            island:register_room_table({
+                room_id = 3,
+                pos = { x = 10, y = 0, z = 0 },
+                extent = { x = 5, y = 5, z = 5 },
+                looping = { y = true },
+                tags = { "generated" },
+                tiles = {
+                    [0] = { kind = "Tile", palette = 4, orientation = "East" },
+                    [5] = { kind = "Door", palette = 1, target = 2 },
+                    [6] = { kind = "Trigger", name = "trap" },
+                },
+            }, { process = function() end })
+
+            local room = island:get_room_by_id(3)
+            assert(room.position.x == 10 and room.extents.z == 5, "Position and extents")
+            assert(room.looping.y and not room.looping.x, "Only y loops")
+            assert(room.tags[1] == "generated", "Tags are kept")
+            assert(room.tile_count == 3, "Three tiles")
+            assert(island:rooms_are_adjacent(2, 3), "Room 3 sits next to room 2")
+        "#,
+        )
+        .exec()
+        .expect("register_room_table failed");
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
         let data = island.data.lock().unwrap();
+        let room = data.rooms.iter().find(|r| r.room_id == 3).unwrap();
+        assert_eq!(room.tiles[&0], TileData::Tile(4, Orientation::East));
+        assert_eq!(room.tiles[&5], TileData::Door(1, 2));
+        assert_eq!(room.tiles[&6], TileData::Trigger("trap".to_string()));
+        assert!(data.room_process_fns.contains_key(&3));
+    }
 
-        // Assert
-        let fields = data
-            .tile_fields
-            .get("sign_tile")
-            .expect("sign_tile not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "messages");
-        assert_eq!(fields[0].field_type, "list");
-        assert_eq!(fields[0].options.item_type, Some("string".to_string()));
+    #[test]
+    fn test_register_room_table_accepts_tiles_from_for_each_world_tile() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            local door
+            island:for_each_world_tile(function(x, y, z, tile)
+                if tile.kind == "door" then
+                    door = tile
+                end
+            end)
+            island:register_room_table({
+                room_id = 3,
+                pos = { x = 10, y = 0, z = 0 },
+                extent = { x = 5, y = 5, z = 5 },
+                tiles = {
+                    [0] = door,
+                    [1] = { kind = "tile", palette = 2, orientation = "West" },
+                },
+            }, {})
+        "#,
+        )
+        .exec()
+        .expect("register_room_table failed");
+
+        let data = island.data.lock().unwrap();
+        let room = data.rooms.iter().find(|r| r.room_id == 3).unwrap();
+        assert_eq!(room.tiles[&0], TileData::Door(1, 2));
+        assert_eq!(room.tiles[&1], TileData::Tile(2, Orientation::West));
     }
 
     #[test]
-    fn test_register_entity_field_with_int_range() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_register_room_table_validates_like_files() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        let failure = |script: &str| lua.load(script).exec().unwrap_err().to_string();
+
+        assert!(
+            failure(r#"island:register_room_table({ room_id = 4, pos = { x = 2 } }, {})"#)
+                .contains("Room 4 overlaps already registered room 1")
+        );
+        assert!(
+            failure(
+                r#"island:register_room_table({
+                    room_id = 4, pos = { x = 50 }, tiles = { [3] = { kind = "None" } },
+                }, {})"#
+            )
+            .contains("room 4 has tile indices outside its 1x1x1 extents: 3")
+        );
+        assert!(
+            failure(
+                r#"island:register_room_table({
+                    room_id = 4, pos = { x = 50 }, tiles = { [0] = { kind = "Door", palette = 1 } },
+                }, {})"#
+            )
+            .contains("Room table 4 tile 0: missing target")
+        );
+        assert!(
+            failure(
+                r#"island:register_room_table({
+                    room_id = 4, pos = { x = 50 }, tiles = { [0] = { kind = "Wall" } },
+                }, {})"#
+            )
+            .contains("unknown kind Wall")
+        );
+        assert!(
+            failure(r#"island:register_room_table({ pos = { x = 50 } }, {})"#)
+                .contains("Room table has no room_id")
+        );
+
+        // A bad callback is caught before the room goes in
+        failure(
+            r#"island:register_room_table({ room_id = 4, pos = { x = 50 } }, { process = 5 })"#,
+        );
+        let data = island.data.lock().unwrap();
+        assert!(data.rooms.iter().all(|r| r.room_id != 4));
+        assert!(!data.room_process_fns.contains_key(&4));
+    }
+
+    #[test]
+    fn test_get_room_by_id() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let tagged_ron = r#"(
+            room_id: 2,
+            pos_x: 5, pos_y: 0, pos_z: 0,
+            extent_x: 5, extent_y: 5, extent_z: 5,
+            looping_z: true,
+            tiles: {
+                0: Tile(3, North),
+                1: Tile(3, North),
+                2: Trigger("bell"),
+            },
+            tags: ["shrine", "quiet"],
+        )"#;
+        std::fs::write(temp_dir.path().join("room_2_tagged.ron"), tagged_ron).unwrap();
+
         let script = r#"
-            island:register_entity_field("npc_basic", "health", "int", { min = 1, max = 1000, default = 100 })
+            island:reload_room(2, "room_2_tagged.ron")
+
+            local dock = island:get_room_by_id(1)
+            assert(dock.room_id == 1 and dock.is_dock, "Room 1 is the dock")
+            assert(dock.tile_count == 1, "Room 1 holds only its door")
+            assert(#dock.tags == 0, "Room 1 has no tags")
+
+            local shrine = island:get_room_by_id(2)
+            assert(not shrine.is_dock, "Room 2 is not the dock")
+            assert(shrine.tile_count == 3, "Room 2 has three tiles")
+            assert(shrine.tags[1] == "shrine" and shrine.tags[2] == "quiet", "Tags in file order")
+            assert(shrine.position.x == 5 and shrine.position.y == 0, "Position")
+            assert(shrine.extents.x == 5 and shrine.extents.z == 5, "Extents")
+            assert(shrine.looping.z and not shrine.looping.x, "Looping per axis")
+
+            assert(island:get_room_by_id(99) == nil, "Unknown room")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
-        let data = island.data.lock().unwrap();
+    #[test]
+    fn test_dock_room_accessors() {
+        let (lua, _island) = create_lua_sandbox_and_island();
+        lua.load(
+            r#"
+            assert(island:get_dock_room_id() == nil, "No dock before the config loads")
+            assert(not pcall(function() return island:is_dock_room(1) end), "is_dock_room needs the config")
+        "#,
+        )
+        .exec()
+        .expect("Failed to execute script");
 
-        // Assert
-        let fields = data
-            .entity_fields
-            .get("npc_basic")
-            .expect("npc_basic not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "health");
-        assert_eq!(fields[0].field_type, "int");
-        assert_eq!(fields[0].options.min, Some(1));
-        assert_eq!(fields[0].options.max, Some(1000));
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            assert(island:get_dock_room_id() == 1, "Dock room from island.ron")
+            assert(island:is_dock_room(1), "Room 1 is the dock")
+            assert(not island:is_dock_room(2), "Room 2 is not")
+        "#,
+        )
+        .exec()
+        .expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_island_metadata_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
+        lua.load(
+            r#"
+            assert(island:get_metadata("author") == nil, "island.ron has no metadata")
+            assert(next(island:get_metadata_all()) == nil, "Missing block reads as empty")
+        "#,
+        )
+        .exec()
+        .expect("Failed to execute script");
+
+        let island_ron = r#"(
+            dock_room_id: 1,
+            name: "Test",
+            description: "Test",
+            metadata: {"author": "Xena", "version": "1.2.0"},
+        )"#;
+        std::fs::write(temp_dir.path().join("credited.ron"), island_ron).unwrap();
+        lua.load(
+            r#"
+            island:load_island_config("credited.ron")
+            assert(island:get_metadata("author") == "Xena", "Author from the config")
+            assert(island:get_metadata("homepage") == nil, "Unset keys are nil")
+            local all = island:get_metadata_all()
+            assert(all.author == "Xena" and all.version == "1.2.0", "Every entry is listed")
+            assert(island:get_dock_room_id() == 1, "Metadata doesn't change gameplay fields")
+        "#,
+        )
+        .exec()
+        .expect("Failed to execute script");
+    }
+
+    #[test]
+    fn test_get_stats_from_luau() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        island.data.lock().unwrap().rooms_mut()[1].tiles.extend([
+            (0, TileData::Tile(3, Default::default())),
+            (1, TileData::Tile(3, Default::default())),
+        ]);
+
+        let script = r#"
+            island:register_tile_field("grass", "height", "int", {})
+            island:register_entity_field("npc", "name", "string", {})
+            island:register_entity_field("chest", "loot", "string", {})
+            assert(island:get_total_tile_count() == 3, "One door plus two tiles")
+
+            local stats = island:get_stats()
+            assert(stats.rooms == 2, "rooms")
+            assert(stats.tiles == 3, "tiles")
+            assert(stats.spawns == 0, "spawns")
+            assert(stats.entity_types == 2, "entity_types")
+            assert(stats.tile_types == 1, "tile_types")
+            assert(stats.gltf_models == 0, "gltf_models")
+        "#;
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_register_entity_field_with_map_schema() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_get_room_grid_size_and_extents_from_luau() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        island.data.lock().unwrap().rooms_mut()[1].extent_z = 2;
+
         let script = r#"
-            island:register_entity_field("npc_basic", "stats", "map", { keys = "string", values = "int" })
+            assert(island:get_room_grid_size(1) == 125, "5x5x5 grid")
+            assert(island:get_room_grid_size(2) == 50, "5x5x2 grid")
+            local extents = island:get_room_extents(2)
+            assert(extents.x == 5 and extents.y == 5 and extents.z == 2, "Extents per axis")
+            assert(island:get_room_grid_size(999) == nil, "Unknown room has no grid")
+            assert(island:get_room_extents(999) == nil, "Unknown room has no extents")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
+    }
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
-        let data = island.data.lock().unwrap();
+    #[test]
+    fn test_get_room_aabb_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
 
-        // Assert
-        let fields = data
-            .entity_fields
-            .get("npc_basic")
-            .expect("npc_basic not found");
-        assert_eq!(fields.len(), 1);
-        assert_eq!(fields[0].field_name, "stats");
-        assert_eq!(fields[0].field_type, "map");
-        assert_eq!(fields[0].options.keys, Some("string".to_string()));
+        let script = r#"
+            local aabb = island:get_room_aabb(2)
+            assert(aabb.min.x == 5 and aabb.min.y == 0 and aabb.min.z == 0, "min corner")
+            assert(aabb.max.x == 10 and aabb.max.y == 5 and aabb.max.z == 5, "max corner")
+            assert(island:get_room_aabb(999) == nil, "Unknown room has no bounds")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_multiple_fields_on_same_entity() {
-        // Arrange
-        let (lua, island) = create_lua_sandbox_and_island();
+    fn test_get_world_bounds_from_luau() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+
         let script = r#"
-            local AIBehavior = {"Idle", "Patrol", "Aggressive"}
-            island:register_entity_field("npc_basic", "health", "int", { default = 100 })
-            island:register_entity_field("npc_basic", "behavior", "enum", { values = AIBehavior, default = "Idle" })
+            local bounds = island:get_world_bounds()
+            assert(bounds.min.x == 0 and bounds.min.y == 0 and bounds.min.z == 0, "min corner")
+            assert(bounds.max.x == 10 and bounds.max.y == 5 and bounds.max.z == 5, "max corner")
+            island:register_room_table({ room_id = 3, pos = { x = -4, y = 9 } }, {})
+            bounds = island:get_world_bounds()
+            assert(bounds.min.x == -4 and bounds.max.y == 10, "Grows with each room")
         "#;
+        lua.load(script).exec().expect("Failed to execute script");
 
-        // Act
-        lua.load(script).exec().expect("failed to execute script");
-        let data = island.data.lock().unwrap();
+        lua.load(
+            r#"
+            island:clear()
+            island:load_island_config("island.ron")
+            assert(island:get_world_bounds() == nil, "No rooms, no bounds")
+        "#,
+        )
+        .exec()
+        .expect("Empty island should have no bounds");
+    }
 
-        // Assert
-        let fields = data
-            .entity_fields
-            .get("npc_basic")
-            .expect("npc_basic not found");
-        assert_eq!(fields.len(), 2);
-        assert_eq!(fields[0].field_name, "health");
-        assert_eq!(fields[1].field_name, "behavior");
+    #[test]
+    fn test_tile_index_queries_from_luau() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        island.data.lock().unwrap().rooms_mut()[1].tiles.extend([
+            (3, TileData::Tile(4, Default::default())),
+            (8, TileData::Tile(4, Default::default())),
+        ]);
+
+        let script = r#"
+            local doors = island:get_door_indices(1)
+            assert(#doors == 1 and doors[1] == 10, "Room 1 has its door at index 10")
+
+            local tiles = island:get_tile_indices(2, 4)
+            assert(#tiles == 2 and tiles[1] == 3 and tiles[2] == 8, "Palette 4 tiles in order")
+            assert(#island:get_tile_indices(2, 9) == 0, "No tiles use palette 9")
+            assert(island:get_door_indices(999) == nil, "Unknown room")
+        "#;
+
+        lua.load(script).exec().expect("Failed to execute script");
     }
 
     #[test]
-    fn test_load_island_config() {
-        use std::fs;
-        use tempfile::TempDir;
+    fn test_tick_prefers_room_process_over_global() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        std::fs::write(
+            temp_dir.path().join("room_3.ron"),
+            r#"(
+                room_id: 3,
+                pos_x: 0, pos_y: 5, pos_z: 0,
+                extent_x: 5, extent_y: 5, extent_z: 5,
+                looping_x: false, looping_y: false, looping_z: false,
+                tiles: {},
+            )"#,
+        )
+        .unwrap();
 
-        let temp_dir = TempDir::new().unwrap();
-        let ron_dir = temp_dir.path().join("ron");
-        fs::create_dir(&ron_dir).unwrap();
+        let script = r#"
+            local calls = { global = 0, room = 0, last_room = nil }
+            island:register_process_fn(function(delta, room_id)
+                calls.global += 1
+                calls.last_room = room_id
+            end)
+            island:register_room("room_3.ron", {
+                process = function(delta)
+                    calls.room += 1
+                end,
+            })
+            return calls
+        "#;
+        let calls: Table = lua.load(script).eval().expect("Failed to execute script");
 
-        let (lua, island) = create_lua_sandbox_and_island();
-        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+        island.tick(&lua, 3, 0.016).expect("room tick failed");
+        assert_eq!(calls.get::<i64>("room").unwrap(), 1);
+        assert_eq!(calls.get::<i64>("global").unwrap(), 0);
 
-        // Create island config file
-        let island_ron = r#"(
-            dock_room_id: 1,
-            name: "Test Island",
-            description: "A test island for loading",
-        )"#;
+        island.tick(&lua, 1, 0.016).expect("global tick failed");
+        assert_eq!(calls.get::<i64>("room").unwrap(), 1);
+        assert_eq!(calls.get::<i64>("global").unwrap(), 1);
+        assert_eq!(calls.get::<u32>("last_room").unwrap(), 1);
+    }
 
-        fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
+    #[test]
+    fn test_call_process_uses_retained_lua() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
+        let script = r#"
+            local calls = { count = 0 }
+            island:register_process_fn(function(delta, room_id)
+                calls.count += 1
+            end)
+            return calls
+        "#;
+        let calls: Table = lua.load(script).eval().expect("Failed to execute script");
+        drop(lua);
 
-        // Create room_1.ron
-        let room_ron = r#"(
-            room_id: 1,
-            pos_x: 0, pos_y: 0, pos_z: 0,
-            extent_x: 5, extent_y: 5, extent_z: 5,
-            looping_x: false, looping_y: false, looping_z: false,
-            tiles: {},
-        )"#;
-        fs::write(ron_dir.join("room_1.ron"), room_ron).unwrap();
+        island.call_process(0.016).expect("call_process failed");
+
+        assert_eq!(calls.get::<i64>("count").unwrap(), 2);
+        assert!(Island::new().call_process(0.016).is_err());
+    }
 
+    #[test]
+    fn test_loaded_fns_run_once_in_order_on_finalize() {
+        let (_temp_dir, lua, island) = load_adjacent_rooms_island();
         let script = r#"
-            island:load_island_config("ron/island.ron")
-            island:register_room("ron/room_1.ron", {})
+            local calls = {}
+            island:register_loaded_fn(function(loaded)
+                table.insert(calls, "first " .. #loaded:get_rooms_with_tag("missing"))
+            end)
+            island:register_loaded_fn(function(loaded)
+                table.insert(calls, "second")
+            end)
+            return calls
         "#;
-        lua.load(script).exec().expect("Failed to execute script");
+        let calls: Table = lua.load(script).eval().expect("Failed to execute script");
+        assert_eq!(calls.raw_len(), 0);
 
-        let data = island.data.lock().unwrap();
-        assert!(data.island_config.is_some());
-        assert_eq!(data.island_config.as_ref().unwrap().name, "Test Island");
-        assert_eq!(data.rooms.len(), 1, "Should have loaded registered room");
+        island.finalize().expect("finalize failed");
+        island.finalize().expect("second finalize failed");
+        let calls: Vec<String> = calls
+            .sequence_values()
+            .collect::<mlua::Result<_>>()
+            .unwrap();
+        assert_eq!(calls, vec!["first 0", "second"]);
     }
 
     #[test]
-    fn test_load_island_config_manual_registration() {
-        use std::fs;
-        use tempfile::TempDir;
+    fn test_finalize_from_script() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let script = r#"
+            local seen = nil
+            island:register_loaded_fn(function(loaded)
+                seen = loaded:get_room_count()
+            end)
+            island:finalize()
+            return seen
+        "#;
+        let seen: usize = lua.load(script).eval().expect("Failed to execute script");
+        assert_eq!(seen, 2);
 
-        let temp_dir = TempDir::new().unwrap();
-        let ron_dir = temp_dir.path().join("ron");
-        fs::create_dir(&ron_dir).unwrap();
+        let err = lua
+            .load(r#"island:register_loaded_fn(function() error("wiring failed") end) island:finalize()"#)
+            .exec()
+            .unwrap_err();
+        assert!(err.to_string().contains("wiring failed"), "{}", err);
+    }
 
-        let (lua, island) = create_lua_sandbox_and_island();
-        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+    #[test]
+    fn test_load_timings_accumulate_per_phase() {
+        let (_temp_dir, lua, _island) = load_adjacent_rooms_island();
+        let script = r#"
+            local before = island:get_load_timings()
+            assert(before.config > 0, "Loading the config was timed")
+            assert(before.rooms > 0, "Registering rooms was timed")
+            assert(before.spawns == 0, "No spawns were loaded")
+            assert(before.validation == 0, "Nothing was validated yet")
 
-        // Create island config file
-        let island_ron = r#"(
-            dock_room_id: 1,
-            name: "Test Island",
-            description: "A test island for loading",
-        )"#;
-        fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
+            island:validate()
+            pcall(function() island:register_room("missing.ron", {}) end)
+            local after = island:get_load_timings()
+            assert(after.validation > 0, "Validation was timed")
+            assert(after.rooms > before.rooms, "Failed loads are timed too")
 
-        // Create room_1.ron (dock room)
-        let room1_ron = r#"(
-            room_id: 1,
-            pos_x: 0, pos_y: 0, pos_z: 0,
-            extent_x: 5, extent_y: 5, extent_z: 5,
-            looping_x: false, looping_y: false, looping_z: false,
-            tiles: {},
-        )"#;
-        fs::write(ron_dir.join("room_1.ron"), room1_ron).unwrap();
+            island:clear()
+            assert(island:get_load_timings().rooms == 0, "Clearing resets the timings")
+        "#;
+        lua.load(script).exec().expect("load timings script failed");
+    }
 
-        // Create room_2.ron (disconnected room)
-        let room2_ron = r#"(
-            room_id: 2,
-            pos_x: 10, pos_y: 0, pos_z: 0,
-            extent_x: 5, extent_y: 5, extent_z: 5,
-            looping_x: false, looping_y: false, looping_z: false,
-            tiles: {},
-        )"#;
-        fs::write(ron_dir.join("room_2.ron"), room2_ron).unwrap();
+    #[test]
+    fn test_export_json_from_luau() {
+        let (temp_dir, lua, _island) = load_adjacent_rooms_island();
 
         let script = r#"
-            island:load_island_config("ron/island.ron")
-            island:register_room("ron/room_1.ron", {})
-            island:register_room("ron/room_2.ron", {})
+            island:register_entity_field("npc", "health", "int", { default = 100, min = 0 })
+            island:export_json("island.json")
         "#;
         lua.load(script).exec().expect("Failed to execute script");
 
-        let data = island.data.lock().unwrap();
-        assert_eq!(data.rooms.len(), 2, "Both rooms should be loaded via explicit registration");
+        let content = std::fs::read_to_string(temp_dir.path().join("island.json")).unwrap();
+        let exported: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(exported["rooms"].as_array().unwrap().len(), 2);
+        assert_eq!(exported["doors"][0]["to"], 2);
+        assert_eq!(exported["adjacency"][0], serde_json::json!([1, 2]));
+        let health = &exported["entity_fields"]["npc"][0];
+        assert_eq!(health["name"], "health");
+        assert_eq!(health["type"], "int");
+        assert_eq!(health["default"].as_f64(), Some(100.0));
+        assert_eq!(health["min"], 0);
+
+        let err = lua
+            .load(r#"island:export_json("../escape.json")"#)
+            .exec()
+            .expect_err("Export outside the base path should fail");
+        assert!(!err.to_string().is_empty());
     }
 
     #[test]
-    fn test_load_entity_spawn() {
-        use std::fs;
-        use tempfile::TempDir;
+    fn test_set_base_path_from_luau() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        let mod_dir = temp_dir.path().join("mods/extra");
+        std::fs::create_dir_all(&mod_dir).unwrap();
+        std::fs::copy(
+            temp_dir.path().join("room_1.ron"),
+            mod_dir.join("mod_room.ron"),
+        )
+        .unwrap();
+        island.data.lock().unwrap().rooms_mut().clear();
 
-        let temp_dir = TempDir::new().unwrap();
-        let ron_dir = temp_dir.path().join("ron").join("spawns");
-        fs::create_dir_all(&ron_dir).unwrap();
+        let err = lua
+            .load(r#"island:set_base_path("mods/missing")"#)
+            .exec()
+            .expect_err("Missing directory should be rejected");
+        assert!(
+            err.to_string()
+                .contains("Base path mods/missing does not exist")
+        );
 
-        let (lua, island) = create_lua_sandbox_and_island();
-        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+        let err = lua
+            .load(r#"island:set_base_path("room_1.ron")"#)
+            .exec()
+            .expect_err("A file is not a content root");
+        assert!(
+            err.to_string()
+                .contains("Base path room_1.ron is not a directory")
+        );
 
-        let spawn_ron = r#"(
-            entity_type: "npc_basic",
-            room_id: 1,
-            grid_index: 5,
-            properties: {
-                "health": "100",
-            },
-        )"#;
+        lua.load(
+            r#"
+            island:set_base_path("mods/extra")
+            island:register_room("mod_room.ron", {})
+        "#,
+        )
+        .exec()
+        .expect("Rooms should resolve against the new base path");
+        assert_eq!(island.data.lock().unwrap().rooms.len(), 1);
 
-        fs::write(ron_dir.join("enemy_1.ron"), spawn_ron).unwrap();
+        let err = lua
+            .load(r#"island:set_base_path("../..")"#)
+            .exec()
+            .expect_err("Base path can't climb out of the current root");
+        assert!(!err.to_string().is_empty());
+    }
 
-        let script = r#"
-            island:load_entity_spawn("ron/spawns/enemy_1.ron")
-            assert(island:get_entity_spawn_count() == 1, "Entity spawn count should be 1")
-        "#;
+    #[test]
+    fn test_register_room_rejects_out_of_bounds_tiles() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        std::fs::write(
+            temp_dir.path().join("room_typo.ron"),
+            r#"(
+                room_id: 3,
+                pos_x: 0, pos_y: 5, pos_z: 0,
+                extent_x: 5, extent_y: 5, extent_z: 5,
+                looping_x: false, looping_y: false, looping_z: false,
+                tiles: {
+                    124: Tile(1, North),
+                    9999: Tile(1, North),
+                },
+            )"#,
+        )
+        .unwrap();
 
-        lua.load(script).exec().expect("Failed to execute script");
+        let err = lua
+            .load(r#"island:register_room("room_typo.ron", {})"#)
+            .exec()
+            .expect_err("Tile index past the room's extents should be rejected");
 
-        let data = island.data.lock().unwrap();
-        assert_eq!(data.entity_spawns.len(), 1);
-        assert_eq!(data.entity_spawns[0].entity_type, "npc_basic");
+        assert!(err.to_string().contains(
+            "Room file room_typo.ron: room 3 has tile indices outside its 5x5x5 extents: 9999"
+        ));
+        assert_eq!(island.data.lock().unwrap().rooms.len(), 2);
     }
 
     #[test]
-    fn test_register_gltf() {
-        use std::fs;
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        fs::create_dir_all(temp_dir.path().join("models")).unwrap();
-
-        let (lua, island) = create_lua_sandbox_and_island();
-        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
-
-        let script = r#"
-            island:register_gltf("character", "models/character.gltf")
-            island:register_gltf("tree", "models/tree.gltf")
-        "#;
+    fn test_register_room_rejects_newer_schema_version() {
+        let (temp_dir, lua, island) = load_adjacent_rooms_island();
+        std::fs::write(
+            temp_dir.path().join("room_future.ron"),
+            r#"(
+                version: 999,
+                room_id: 3,
+                pos_x: 0, pos_y: 5, pos_z: 0,
+                extent_x: 5, extent_y: 5, extent_z: 5,
+                looping_x: false, looping_y: false, looping_z: false,
+                tiles: {},
+            )"#,
+        )
+        .unwrap();
 
-        lua.load(script).exec().expect("Failed to execute script");
+        let err = lua
+            .load(r#"island:register_room("room_future.ron", {})"#)
+            .exec()
+            .expect_err("Newer schema should be rejected");
 
-        let data = island.data.lock().unwrap();
-        assert_eq!(data.gltf_registry.len(), 2);
-        assert!(data.gltf_registry.contains_key("character"));
-        assert!(data.gltf_registry.contains_key("tree"));
+        assert!(err.to_string().contains(
+            "Room file room_future.ron: schema version 999 is newer than the supported version"
+        ));
+        assert_eq!(island.data.lock().unwrap().rooms.len(), 2);
     }
 
     #[test]
-    fn test_rooms_are_adjacent_from_luau() {
+    fn test_check_doors_reports_missing_target() {
         use std::fs;
         use tempfile::TempDir;
 
@@ -675,7 +5486,6 @@ mod test {
         let (lua, island) = create_lua_sandbox_and_island();
         island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
 
-        // Create island config
         let island_ron = r#"(
             dock_room_id: 1,
             name: "Test",
@@ -683,7 +5493,7 @@ mod test {
         )"#;
         fs::write(ron_dir.join("island.ron"), island_ron).unwrap();
 
-        // Create two adjacent rooms with door connection
+        // Door 12 points at a room that is never registered
         let room1_ron = r#"(
             room_id: 1,
             pos_x: 0, pos_y: 0, pos_z: 0,
@@ -691,9 +5501,9 @@ mod test {
             looping_x: false, looping_y: false, looping_z: false,
             tiles: {
                 10: Door(1, 2),
+                12: Door(1, 7),
             },
         )"#;
-
         let room2_ron = r#"(
             room_id: 2,
             pos_x: 5, pos_y: 0, pos_z: 0,
@@ -701,7 +5511,6 @@ mod test {
             looping_x: false, looping_y: false, looping_z: false,
             tiles: {},
         )"#;
-
         fs::write(ron_dir.join("room_1.ron"), room1_ron).unwrap();
         fs::write(ron_dir.join("room_2.ron"), room2_ron).unwrap();
 
@@ -710,14 +5519,23 @@ mod test {
             island:register_room("ron/room_1.ron", {})
             island:register_room("ron/room_2.ron", {})
 
-            local adjacent = island:rooms_are_adjacent(1, 2)
-            assert(adjacent == true, "Rooms should be adjacent")
-
-            local not_adjacent = island:rooms_are_adjacent(1, 999)
-            assert(not_adjacent == false, "Non-existent room should not be adjacent")
+            local problems = island:validate()
+            assert(#problems == 1, "Expected exactly one problem")
         "#;
-
         lua.load(script).exec().expect("Failed to execute script");
+
+        let err = lua
+            .load("island:check_doors()")
+            .exec()
+            .expect_err("Dangling door should fail check_doors");
+        assert!(
+            err.to_string()
+                .contains("Door in room 1 at grid index 12 targets missing room 7")
+        );
+        assert_eq!(
+            island.validate(),
+            vec!["Door in room 1 at grid index 12 targets missing room 7"]
+        );
     }
 
     #[test]
@@ -758,6 +5576,214 @@ mod test {
         assert_eq!(data.entity_fields.get("npc_basic").unwrap().len(), 4);
     }
 
+    #[test]
+    fn test_script_reads_context() {
+        let (lua, _island) = create_lua_sandbox_and_island_with_context(HashMap::from([
+            ("difficulty".to_string(), "hard".to_string()),
+            ("dlc_caves".to_string(), "true".to_string()),
+        ]));
+        let script = r#"
+            local rooms = 1
+            if context.difficulty == "hard" then
+                rooms += 1
+            end
+            if context.dlc_caves == "true" then
+                rooms += 10
+            end
+            return rooms
+        "#;
+        let rooms: i64 = lua.load(script).eval().expect("failed to execute script");
+        assert_eq!(rooms, 12);
+        assert!(
+            lua.load(r#"context.difficulty = "easy""#).exec().is_err(),
+            "context is read-only"
+        );
+
+        let (lua, _island) = create_lua_sandbox_and_island();
+        let difficulty: Option<String> = lua
+            .load("return context.difficulty")
+            .eval()
+            .expect("default sandbox has an empty context");
+        assert_eq!(difficulty, None);
+    }
+
+    #[test]
+    fn test_untrusted_sandbox_cannot_touch_the_filesystem() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for file in ["island.ron", "room.ron", "npc.ron", "tree.glb"] {
+            std::fs::write(temp_dir.path().join(file), "").unwrap();
+        }
+        let (lua, island) = create_lua_sandbox_with_limits(SandboxLimits::UNTRUSTED);
+        island.set_base_path(temp_dir.path().to_path_buf());
+
+        for call in [
+            r#"island:load_island_config("island.ron")"#,
+            r#"island:register_room("room.ron", {})"#,
+            r#"island:load_entity_spawn("npc.ron")"#,
+            r#"island:register_gltf("tree", "tree.glb")"#,
+            r#"island:register_rooms_from_dir(".")"#,
+        ] {
+            let err = lua.load(call).exec().unwrap_err();
+            assert!(
+                matches!(
+                    IslandError::from_lua_error(&err),
+                    Some(IslandError::PathSecurity {
+                        rejection: PathRejection::FilesystemDisabled,
+                        ..
+                    })
+                ),
+                "{} should be refused, got {}",
+                call,
+                err
+            );
+            assert!(err.to_string().contains("filesystem access disabled"));
+        }
+
+        // In-memory loading still works, and clearing doesn't hand back filesystem access
+        lua.load(
+            r#"
+            island:register_room_table({ room_id = 1, extent = { x = 3, y = 3, z = 3 } }, {})
+            assert(island:get_room_count() == 1, "Inline rooms are allowed")
+            island:clear()
+        "#,
+        )
+        .exec()
+        .expect("Inline loading should work without filesystem access");
+        assert!(
+            lua.load(r#"island:register_room("room.ron", {})"#)
+                .exec()
+                .is_err()
+        );
+
+        let (trusted_lua, trusted_island) = create_lua_sandbox_and_island();
+        trusted_island.set_base_path(temp_dir.path().to_path_buf());
+        let err = trusted_lua
+            .load(r#"island:register_room("room.ron", {})"#)
+            .exec()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("[parse]"),
+            "Trusted sandboxes read the file, got {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_instruction_budget_aborts_runaway_script() {
+        let (lua, island) = create_lua_sandbox_with_limits(SandboxLimits {
+            max_instructions: 10_000,
+            ..SandboxLimits::UNTRUSTED
+        });
+
+        let err = lua
+            .load("while true do end")
+            .exec()
+            .expect_err("Infinite loop should exceed the budget");
+        assert!(err.to_string().contains("instruction budget of 10000"));
+
+        // A fresh budget lets well-behaved scripts run again
+        island.reset_instruction_budget();
+        lua.load(r#"island:set_tile_layers({"Floor"})"#)
+            .exec()
+            .expect("Script should run after the budget is reset");
+        assert_eq!(island.get_tile_layers(), vec!["Floor"]);
+    }
+
+    #[test]
+    fn test_memory_limit_aborts_unbounded_allocation() {
+        let (lua, _island) = create_lua_sandbox_with_memory_limit(2 * 1024 * 1024);
+        let script = r#"
+            local hoard = {}
+            for i = 1, 10000000 do
+                hoard[i] = { i, i, i }
+            end
+        "#;
+
+        let err = lua
+            .load(script)
+            .exec()
+            .expect_err("Unbounded allocation should exceed the memory limit");
+        assert!(matches!(err, LuaError::MemoryError(_)), "got {:?}", err);
+    }
+
+    #[test]
+    fn test_print_is_forwarded_as_one_tagged_line() {
+        let (lua, _island) = create_lua_sandbox_and_island();
+        lua.load(r#"print("hello", 42, nil, true, {})"#)
+            .exec()
+            .expect("print should accept any values");
+
+        let values: Variadic<Value> = lua
+            .load(r#"return "hello", 42, nil, true, {}"#)
+            .eval()
+            .unwrap();
+        let line = print_line(&lua, Path::new("mods/demo"), &values).unwrap();
+        assert!(
+            line.starts_with("[mods/demo] hello\t42\tnil\ttrue\ttable: "),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn test_seeded_random_is_reproducible() {
+        let script = r#"
+            island:set_seed(1234)
+            local rolls = {}
+            for i = 1, 16 do
+                local roll = island:random_int(1, 6)
+                assert(roll >= 1 and roll <= 6, "roll out of range")
+                local f = island:random()
+                assert(f >= 0 and f < 1, "float out of range")
+                table.insert(rolls, roll)
+            end
+            return table.concat(rolls, ",")
+        "#;
+
+        let (lua_a, _island_a) = create_lua_sandbox_and_island();
+        let (lua_b, _island_b) = create_lua_sandbox_and_island();
+        let rolls_a: String = lua_a.load(script).eval().expect("script a failed");
+        let rolls_b: String = lua_b.load(script).eval().expect("script b failed");
+        assert_eq!(rolls_a, rolls_b);
+
+        let other_seed: String = lua_b
+            .load(&script.replace("1234", "4321"))
+            .eval()
+            .expect("script with other seed failed");
+        assert_ne!(rolls_a, other_seed);
+
+        assert!(lua_a.load("island:random_int(5, 1)").exec().is_err());
+    }
+
+    #[test]
+    fn test_errors_report_file_and_line() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let (lua, island) = create_lua_sandbox_and_island();
+        island.data.lock().unwrap().base_path = temp_dir.path().to_path_buf();
+
+        let broken_room = "(\n    room_id: oops,\n)";
+        fs::write(temp_dir.path().join("room_1.ron"), broken_room).unwrap();
+
+        let err = lua
+            .load(r#"island:register_room("room_1.ron", {})"#)
+            .exec()
+            .expect_err("Broken room file should fail to register");
+        let message = err.to_string();
+        assert!(message.contains("room_1.ron:2:"), "got {}", message);
+
+        let script = "island:set_tile_layers({})\n\nerror(\"boom\")\n";
+        let script_path = temp_dir.path().join("island.luau");
+        fs::write(&script_path, script).unwrap();
+
+        let err =
+            exec_island_script(&lua, &script_path).expect_err("Script error should propagate");
+        let message = err.to_string();
+        assert!(message.contains("island.luau:3: boom"), "got {}", message);
+    }
+
     #[test]
     fn test_load_tbol_vanilla() {
         // Arrange
@@ -773,11 +5799,10 @@ mod test {
 
         let base_path = island.data.lock().unwrap().base_path.clone();
         let script_path = base_path.join("island.luau");
-        let script = std::fs::read_to_string(&script_path)
-            .unwrap_or_else(|e| panic!("Failed to read island.luau from {:?}: {}", script_path, e));
 
         // Act
-        lua.load(&script).exec().expect("Failed to execute vanilla island.luau");
+        exec_island_script(&lua, &script_path)
+            .unwrap_or_else(|e| panic!("Failed to execute vanilla island.luau: {}", e));
 
         // Assert
         let data = island.data.lock().unwrap();
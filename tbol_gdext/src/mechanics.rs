@@ -2,46 +2,343 @@ use ghx_grid::cartesian::coordinates::Cartesian3D;
 use ghx_grid::cartesian::grid::CartesianGrid;
 use ghx_grid::grid::{GridData, GridIndex};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::json;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
 
 pub type StringPath = String;
 pub type StringContent = String;
 pub type PaletteIndex = u32;
 pub type RoomId = u32;
 
-/// Runtime island state - loaded from individual files, not serialized as a whole
-#[derive(Clone, Debug)]
+/// Runtime island state - loaded from individual files, or bundled as one RON file via `to_ron`
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct IslandData {
     pub island: Island,
-    pub rooms: Vec<Room>,
+    /// Read with `rooms`; changes go through `rooms_mut` so the spatial index stays current
+    rooms: Vec<Room>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_spawns: Vec<EntitySpawn>,
+    #[serde(default, skip_serializing_if = "WorldWrap::is_unbounded")]
+    pub world_wrap: WorldWrap,
+    /// Movement cost per palette for `find_path_weighted`; unlisted palettes cost 1
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tile_costs: HashMap<PaletteIndex, f64>,
+    /// Speeds up `room_at` and adjacency on large islands. Built on first use and dropped
+    /// by `rooms_mut`, so it never describes rooms that have since moved.
+    #[serde(skip)]
+    spatial_index: OnceLock<RoomGridIndex>,
+}
+
+/// World size along each axis that wraps around, so a room touching the far edge meets
+/// one at the near edge. `None` leaves the axis unbounded, the default for every axis.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorldWrap {
+    pub x: Option<i64>,
+    pub y: Option<i64>,
+    pub z: Option<i64>,
+}
+
+impl WorldWrap {
+    pub fn is_unbounded(&self) -> bool {
+        self.x.is_none() && self.y.is_none() && self.z.is_none()
+    }
+
+    /// Every translation that maps a position onto one of its wrapped copies, (0, 0, 0) included
+    fn offsets(&self) -> Vec<(i64, i64, i64)> {
+        let shifts = |size: Option<i64>| match size {
+            Some(size) => vec![-size, 0, size],
+            None => vec![0],
+        };
+        let mut offsets = Vec::new();
+        for dx in shifts(self.x) {
+            for dy in shifts(self.y) {
+                for dz in shifts(self.z) {
+                    offsets.push((dx, dy, dz));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+/// RON schema version this build reads and writes for `Island` and `Room`
+/// Bump it whenever either changes shape, and add the upgrade step to their `migrate`.
+/// Also bump it when the `to_bincode` layout changes, so stale bakes are turned away.
+///
+/// 2: spawns gained `rotation`, `scale` and `id`; islands gained `tile_costs`
+pub const SCHEMA_VERSION: u32 = 2;
+
+fn current_schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// Axes left out of a hand-written room are one tile thick
+fn default_extent() -> u32 {
+    1
+}
+
+/// Reject data written for a newer schema than this build understands
+pub fn check_schema_version(version: u32) -> Result<(), String> {
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "schema version {} is newer than the supported version {}",
+            version, SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Any negative movement cost marks a palette as impassable for `find_path_weighted`
+pub const IMPASSABLE_COST: f64 = -1.0;
+
+/// Min and max world corners of a room, as returned by `Room::aabb`
+pub type RoomBounds = ((i64, i64, i64), (i64, i64, i64));
+
+/// `bounds` moved by one of the `WorldWrap::offsets`
+fn shift_bounds(bounds: RoomBounds, (dx, dy, dz): (i64, i64, i64)) -> RoomBounds {
+    let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = bounds;
+    (
+        (min_x + dx, min_y + dy, min_z + dz),
+        (max_x + dx, max_y + dy, max_z + dz),
+    )
+}
+
+/// A tile in a specific room, the node type for tile-level navigation
+pub type TileLocation = (RoomId, GridIndex);
+
+/// Dijkstra frontier entry, ordered so `BinaryHeap` pops the cheapest first
+struct Frontier {
+    cost: f64,
+    location: TileLocation,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| self.location.cmp(&other.location))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Start of every `to_bincode` file, so other files are turned away before decoding
+const BINCODE_MAGIC: &[u8; 4] = b"TBOL";
+
+/// `IslandData` as written by `to_bincode`. Bincode isn't self-describing, so every field
+/// is always written, including the ones RON leaves out when empty; the schema version is
+/// written once up front instead of per room.
+#[derive(Serialize, Deserialize)]
+struct PackedIsland {
+    dock_room_id: RoomId,
+    name: StringContent,
+    description: StringContent,
+    metadata: HashMap<String, String>,
+    rooms: Vec<PackedRoom>,
+    entity_spawns: Vec<EntitySpawn>,
+    world_wrap: WorldWrap,
+    tile_costs: HashMap<PaletteIndex, f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackedRoom {
+    room_id: RoomId,
+    pos: (i64, i64, i64),
+    extent: (u32, u32, u32),
+    looping: (bool, bool, bool),
+    tiles: HashMap<GridIndex, TileData>,
+    tags: Vec<String>,
+}
+
+impl From<&Room> for PackedRoom {
+    fn from(room: &Room) -> Self {
+        PackedRoom {
+            room_id: room.room_id,
+            pos: (room.pos_x, room.pos_y, room.pos_z),
+            extent: (room.extent_x, room.extent_y, room.extent_z),
+            looping: (room.looping_x, room.looping_y, room.looping_z),
+            tiles: room.tiles.clone(),
+            tags: room.tags.clone(),
+        }
+    }
+}
+
+impl From<PackedRoom> for Room {
+    fn from(room: PackedRoom) -> Self {
+        Room {
+            version: SCHEMA_VERSION,
+            room_id: room.room_id,
+            pos_x: room.pos.0,
+            pos_y: room.pos.1,
+            pos_z: room.pos.2,
+            extent_x: room.extent.0,
+            extent_y: room.extent.1,
+            extent_z: room.extent.2,
+            looping_x: room.looping.0,
+            looping_y: room.looping.1,
+            looping_z: room.looping.2,
+            tiles: room.tiles,
+            tags: room.tags,
+        }
+    }
+}
+
+/// Rooms spanning more grid cells than this skip the cells and are checked on every lookup
+const MAX_INDEXED_CELLS_PER_ROOM: i64 = 64;
+
+/// Uniform grid hash over room bounds. Each room is listed in every cell its bounds touch,
+/// faces included, so rooms that contain a point or touch each other always share a cell.
+/// Lookups return positions in `IslandData::rooms` that callers still test exactly.
+/// Positions go stale when rooms change, so `IslandData` drops the index whenever they can.
+#[derive(Clone, Debug)]
+struct RoomGridIndex {
+    cell_size: i64,
+    cells: HashMap<(i64, i64, i64), Vec<usize>>,
+    /// Rooms too big to list per cell
+    oversized: Vec<usize>,
+}
+
+impl RoomGridIndex {
+    fn build(rooms: &[Room]) -> Self {
+        // Cells about the size of an average room keep rooms per cell and cells per room small
+        let largest_extents: i64 = rooms
+            .iter()
+            .map(|room| room.extent_x.max(room.extent_y).max(room.extent_z) as i64)
+            .sum();
+        let cell_size = (largest_extents / rooms.len().max(1) as i64).max(1);
+        let mut index = RoomGridIndex {
+            cell_size,
+            cells: HashMap::new(),
+            oversized: Vec::new(),
+        };
+        for (position, room) in rooms.iter().enumerate() {
+            let (min, max) = index.cell_range(room.aabb());
+            let cell_count = (max.0 - min.0 + 1) * (max.1 - min.1 + 1) * (max.2 - min.2 + 1);
+            if cell_count > MAX_INDEXED_CELLS_PER_ROOM {
+                index.oversized.push(position);
+                continue;
+            }
+            for cx in min.0..=max.0 {
+                for cy in min.1..=max.1 {
+                    for cz in min.2..=max.2 {
+                        index.cells.entry((cx, cy, cz)).or_default().push(position);
+                    }
+                }
+            }
+        }
+        index
+    }
+
+    fn cell(&self, (x, y, z): (i64, i64, i64)) -> (i64, i64, i64) {
+        (
+            x.div_euclid(self.cell_size),
+            y.div_euclid(self.cell_size),
+            z.div_euclid(self.cell_size),
+        )
+    }
+
+    fn cell_range(&self, (min, max): RoomBounds) -> RoomBounds {
+        (self.cell(min), self.cell(max))
+    }
+
+    /// Rooms that may contain the point, sorted
+    fn near_point(&self, point: (i64, i64, i64)) -> Vec<usize> {
+        let mut near = self.oversized.clone();
+        near.extend(self.cells.get(&self.cell(point)).into_iter().flatten());
+        near.sort_unstable();
+        near
+    }
+
+    /// Rooms that may overlap or touch `bounds`, sorted and deduplicated
+    fn near_bounds(&self, bounds: RoomBounds) -> Vec<usize> {
+        let (min, max) = self.cell_range(bounds);
+        let mut near = self.oversized.clone();
+        for cx in min.0..=max.0 {
+            for cy in min.1..=max.1 {
+                for cz in min.2..=max.2 {
+                    near.extend(self.cells.get(&(cx, cy, cz)).into_iter().flatten());
+                }
+            }
+        }
+        near.sort_unstable();
+        near.dedup();
+        near
+    }
 }
 
 /// Core island configuration - serialized to RON by editor
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Island {
+    /// Files without a version are read as the current schema
+    #[serde(default = "current_schema_version")]
+    pub version: u32,
     pub dock_room_id: RoomId,
     pub name: StringContent,
     pub description: StringContent,
+    /// Free-form attribution such as author, version and homepage for mod tooling;
+    /// gameplay never reads it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Partial island configuration layered over a base island by mods
+/// Only the fields present override the base
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct IslandPatch {
+    pub dock_room_id: Option<RoomId>,
+    pub name: Option<StringContent>,
+    pub description: Option<StringContent>,
+    /// Entries added to the base metadata, replacing any with the same key
+    pub metadata: HashMap<String, String>,
 }
 
 /// Room definition - serialized to RON by editor
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Room {
+    /// Files without a version are read as the current schema
+    #[serde(default = "current_schema_version")]
+    pub version: u32,
     pub room_id: RoomId,
     /// World position (for adjacency checks)
     pub pos_x: i64,
     pub pos_y: i64,
     pub pos_z: i64,
     /// Grid extents
+    #[serde(default = "default_extent")]
     pub extent_x: u32,
+    #[serde(default = "default_extent")]
     pub extent_y: u32,
+    #[serde(default = "default_extent")]
     pub extent_z: u32,
-    /// Looping per axis
+    /// Looping per axis, off unless the file says otherwise
+    #[serde(default)]
     pub looping_x: bool,
+    #[serde(default)]
     pub looping_y: bool,
+    #[serde(default)]
     pub looping_z: bool,
     /// Tile data: grid index -> tile
     pub tiles: HashMap<GridIndex, TileData>,
+    /// Free-form labels such as "forest" or "boss" that editors group and filter rooms by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 /// Entity spawn point - serialized to RON by editor
@@ -52,71 +349,1002 @@ pub struct EntitySpawn {
     pub grid_index: GridIndex,
     /// Luau-defined properties serialized as strings
     pub properties: HashMap<StringContent, StringContent>,
+    /// Yaw in degrees; None keeps the entity type's default facing
+    #[serde(default)]
+    pub rotation: Option<f32>,
+    /// Uniform scale; None keeps the entity type's default size
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Stable name other spawns' `entity_ref` properties point at
+    #[serde(default)]
+    pub id: Option<StringContent>,
 }
 
 /// Minimal tile data - Luau defines semantics via register_tile_field
 #[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
 pub enum TileData {
     None,
-    /// Palette index references GLTF model
-    Tile(PaletteIndex),
+    /// Palette index references GLTF model, rotated by orientation
+    Tile(PaletteIndex, #[serde(default)] Orientation),
     /// Door connects to another room (no adjacency needed)
     Door(PaletteIndex, RoomId),
+    /// Named trigger zone (traps, cutscenes) - Luau decides what the name means
+    Trigger(StringContent),
+}
+
+/// Yaw rotation of a tile model in quarter turns around the up axis
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Debug, Default)]
+pub enum Orientation {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Orientation {
+    /// Clockwise yaw in degrees, North being the model's authored facing
+    pub fn yaw_degrees(self) -> f32 {
+        match self {
+            Orientation::North => 0.0,
+            Orientation::East => 90.0,
+            Orientation::South => 180.0,
+            Orientation::West => 270.0,
+        }
+    }
+}
+
+/// Why two rooms are or aren't adjacent, for debugging navigation on large islands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjacencyReason {
+    Adjacent,
+    NotTouching,
+    RoomMissing(RoomId),
+    SameRoom,
+}
+
+impl std::fmt::Display for AdjacencyReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdjacencyReason::Adjacent => write!(f, "adjacent"),
+            AdjacencyReason::NotTouching => write!(f, "not_touching"),
+            AdjacencyReason::RoomMissing(room_id) => write!(f, "room_missing:{}", room_id),
+            AdjacencyReason::SameRoom => write!(f, "same_room"),
+        }
+    }
+}
+
+/// Side of a room's bounds, named by the axis and direction it faces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Face {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl std::fmt::Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Face::PosX => write!(f, "+x"),
+            Face::NegX => write!(f, "-x"),
+            Face::PosY => write!(f, "+y"),
+            Face::NegY => write!(f, "-y"),
+            Face::PosZ => write!(f, "+z"),
+            Face::NegZ => write!(f, "-z"),
+        }
+    }
+}
+
+/// How a path step gets from one room into the next
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    /// Walking across the shared `face` of the room being left
+    Adjacent { face: Face },
+    /// Opening a door tile drawn with `palette`
+    Door { palette: PaletteIndex },
+}
+
+/// One room entered along a path and the edge used to enter it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathStep {
+    pub room_id: RoomId,
+    pub via: Edge,
 }
 
 impl IslandData {
     pub fn new(island: Island, rooms: Vec<Room>) -> Self {
-        Self { island, rooms }
+        Self {
+            island,
+            rooms,
+            entity_spawns: Vec::new(),
+            world_wrap: WorldWrap::default(),
+            tile_costs: HashMap::new(),
+            spatial_index: OnceLock::new(),
+        }
+    }
+
+    /// Registered rooms, in registration order
+    pub fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    /// Rooms for editing in place; the spatial index is rebuilt on the next lookup
+    pub fn rooms_mut(&mut self) -> &mut Vec<Room> {
+        self.spatial_index.take();
+        &mut self.rooms
+    }
+
+    fn spatial_index(&self) -> &RoomGridIndex {
+        self.spatial_index
+            .get_or_init(|| RoomGridIndex::build(&self.rooms))
+    }
+
+    /// Positions in `rooms` of rooms whose bounds may contain the point, ascending
+    fn positions_near_point(&self, x: i64, y: i64, z: i64) -> Vec<usize> {
+        self.spatial_index().near_point((x, y, z))
+    }
+
+    /// Positions in `rooms` of rooms that may touch `room`, ascending. A wrapping world can
+    /// join rooms from opposite ends, so it always gets every room.
+    fn positions_near(&self, room: &Room) -> Vec<usize> {
+        if self.world_wrap.is_unbounded() {
+            self.spatial_index().near_bounds(room.aabb())
+        } else {
+            (0..self.rooms.len()).collect()
+        }
+    }
+
+    /// Bundle the island, its rooms and any spawns into a single RON document
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("island data contains only RON-serializable types")
+    }
+
+    /// Parse an island saved by `to_ron`, turning away island configs or rooms written for
+    /// a newer schema and migrating older ones
+    pub fn from_ron(content: &str) -> Result<IslandData, String> {
+        let mut island_data: IslandData = ron::from_str(content).map_err(|e| e.to_string())?;
+        check_schema_version(island_data.island.version).map_err(|e| format!("island: {}", e))?;
+        for room in &island_data.rooms {
+            check_schema_version(room.version)
+                .map_err(|e| format!("room {}: {}", room.room_id, e))?;
+        }
+        island_data.island = island_data.island.migrate();
+        island_data.rooms = island_data.rooms.into_iter().map(Room::migrate).collect();
+        Ok(island_data)
+    }
+
+    /// Compact binary form for shipping pre-baked islands; RON stays the editing format.
+    /// Only builds with the same `SCHEMA_VERSION` can read it back.
+    pub fn to_bincode(&self) -> Vec<u8> {
+        let packed = PackedIsland {
+            dock_room_id: self.island.dock_room_id,
+            name: self.island.name.clone(),
+            description: self.island.description.clone(),
+            metadata: self.island.metadata.clone(),
+            rooms: self.rooms.iter().map(PackedRoom::from).collect(),
+            entity_spawns: self.entity_spawns.clone(),
+            world_wrap: self.world_wrap,
+            tile_costs: self.tile_costs.clone(),
+        };
+        let mut bytes = BINCODE_MAGIC.to_vec();
+        bytes.extend(SCHEMA_VERSION.to_le_bytes());
+        bincode::serde::encode_into_std_write(&packed, &mut bytes, bincode::config::standard())
+            .expect("island data contains only bincode-serializable types");
+        bytes
+    }
+
+    pub fn from_bincode(bytes: &[u8]) -> Result<IslandData, String> {
+        let Some(payload) = bytes.strip_prefix(BINCODE_MAGIC) else {
+            return Err("not a binary island file".to_string());
+        };
+        let Some((version, payload)) = payload.split_first_chunk::<4>() else {
+            return Err("binary island ends before its schema version".to_string());
+        };
+        let version = u32::from_le_bytes(*version);
+        if version != SCHEMA_VERSION {
+            return Err(format!(
+                "binary island has schema version {}, this build reads {}; re-bake it from RON",
+                version, SCHEMA_VERSION
+            ));
+        }
+        let (packed, read): (PackedIsland, usize) =
+            bincode::serde::decode_from_slice(payload, bincode::config::standard())
+                .map_err(|e| e.to_string())?;
+        if read != payload.len() {
+            return Err(format!(
+                "{} unexpected bytes after the island",
+                payload.len() - read
+            ));
+        }
+
+        let island = Island {
+            version: SCHEMA_VERSION,
+            dock_room_id: packed.dock_room_id,
+            name: packed.name,
+            description: packed.description,
+            metadata: packed.metadata,
+        };
+        let rooms = packed.rooms.into_iter().map(Room::from).collect();
+        let mut island_data = IslandData::new(island, rooms);
+        island_data.entity_spawns = packed.entity_spawns;
+        island_data.world_wrap = packed.world_wrap;
+        island_data.tile_costs = packed.tile_costs;
+        Ok(island_data)
+    }
+
+    /// Read-only topology snapshot for external tools that can't read RON:
+    /// island config, rooms, door edges and physically adjacent room pairs
+    pub fn to_json_value(&self) -> serde_json::Value {
+        let doors: Vec<serde_json::Value> = self
+            .doors()
+            .into_iter()
+            .map(|(from, to, palette)| json!({ "from": from, "to": to, "palette": palette }))
+            .collect();
+        let mut adjacency = Vec::new();
+        for (i, a) in self.rooms.iter().enumerate() {
+            for j in self.positions_near(a).into_iter().filter(|&j| j > i) {
+                let b = &self.rooms[j];
+                if Room::are_adjacent_wrapped(a, b, &self.world_wrap) {
+                    adjacency.push([a.room_id, b.room_id]);
+                }
+            }
+        }
+        json!({
+            "island": self.island,
+            "rooms": self.rooms,
+            "doors": doors,
+            "adjacency": adjacency,
+            "tile_costs": self.tile_costs,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json_value())
+            .expect("island data contains only JSON-serializable types")
     }
 
     /// Check if two rooms are physically adjacent (share a face)
     /// This allows navigation without explicit doors (haunted house mechanics)
     pub fn rooms_are_adjacent(&self, room_a_id: RoomId, room_b_id: RoomId) -> bool {
-        if room_a_id == room_b_id {
-            return false;
+        self.adjacency_reason(room_a_id, room_b_id) == AdjacencyReason::Adjacent
+    }
+
+    /// The outcome of `rooms_are_adjacent` with its cause; a missing room is reported before
+    /// the same-room check, and room A before room B
+    pub fn adjacency_reason(&self, room_a_id: RoomId, room_b_id: RoomId) -> AdjacencyReason {
+        let room_a = self.rooms.iter().find(|r| r.room_id == room_a_id);
+        let room_b = self.rooms.iter().find(|r| r.room_id == room_b_id);
+
+        match (room_a, room_b) {
+            (None, _) => AdjacencyReason::RoomMissing(room_a_id),
+            (_, None) => AdjacencyReason::RoomMissing(room_b_id),
+            _ if room_a_id == room_b_id => AdjacencyReason::SameRoom,
+            (Some(a), Some(b)) if Room::are_adjacent_wrapped(a, b, &self.world_wrap) => {
+                AdjacencyReason::Adjacent
+            }
+            _ => AdjacencyReason::NotTouching,
+        }
+    }
+
+    /// List every door edge as (owning room, target room, door palette index)
+    /// Ordered by room registration order, then by grid index within a room
+    pub fn doors(&self) -> Vec<(RoomId, RoomId, PaletteIndex)> {
+        let mut doors = Vec::new();
+        for room in &self.rooms {
+            let mut room_doors: Vec<(GridIndex, RoomId, PaletteIndex)> = room
+                .tiles
+                .iter()
+                .filter_map(|(index, tile)| match tile {
+                    TileData::Door(palette, target) => Some((*index, *target, *palette)),
+                    _ => None,
+                })
+                .collect();
+            room_doors.sort_by_key(|(index, _, _)| *index);
+            doors.extend(
+                room_doors
+                    .into_iter()
+                    .map(|(_, target, palette)| (room.room_id, target, palette)),
+            );
+        }
+        doors
+    }
+
+    /// Find doors whose target room was never registered, as (room, grid index, target)
+    pub fn dangling_doors(&self) -> Vec<(RoomId, GridIndex, RoomId)> {
+        let mut dangling = Vec::new();
+        for room in &self.rooms {
+            for (index, tile) in &room.tiles {
+                if let TileData::Door(_, target) = tile
+                    && !self.rooms.iter().any(|r| r.room_id == *target)
+                {
+                    dangling.push((room.room_id, *index, *target));
+                }
+            }
+        }
+        dangling.sort();
+        dangling
+    }
+
+    /// Doors on a face of a looping axis, as (room, grid index): wrapping makes that face
+    /// lead back into the room, so there is no well-defined other side to open onto
+    pub fn doors_on_looping_faces(&self) -> Vec<(RoomId, GridIndex)> {
+        let mut doors = Vec::new();
+        for room in &self.rooms {
+            for (index, tile) in &room.tiles {
+                if matches!(tile, TileData::Door(_, _)) && room.is_on_looping_face(*index) {
+                    doors.push((room.room_id, *index));
+                }
+            }
         }
+        doors.sort();
+        doors
+    }
+
+    /// Find (from, to) door connections with no door leading back from `to` to `from`
+    /// One-way drops can be intentional, so callers should treat these as warnings
+    /// Doors into missing rooms are reported by `dangling_doors` instead
+    pub fn unpaired_doors(&self) -> Vec<(RoomId, RoomId)> {
+        let doors = self.doors();
+        let mut unpaired: Vec<(RoomId, RoomId)> = doors
+            .iter()
+            .filter(|(_, to, _)| self.rooms.iter().any(|r| r.room_id == *to))
+            .filter(|(from, to, _)| !doors.iter().any(|(f, t, _)| f == to && t == from))
+            .map(|(from, to, _)| (*from, *to))
+            .collect();
+        unpaired.sort();
+        unpaired.dedup();
+        unpaired
+    }
+
+    /// Rooms reachable in one step from `room_id`, by sharing a face or through one of its doors
+    /// Sorted and deduplicated; doors into unregistered rooms are skipped
+    pub fn connected_rooms(&self, room_id: RoomId) -> Vec<RoomId> {
+        self.connections(room_id)
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect()
+    }
+
+    /// Union of every room's `aabb`, for sizing a camera or streaming grid to the whole island
+    /// None when no rooms are registered
+    pub fn world_bounds(&self) -> Option<RoomBounds> {
+        self.rooms
+            .iter()
+            .map(Room::aabb)
+            .reduce(|(min_a, max_a), (min_b, max_b)| {
+                (
+                    (
+                        min_a.0.min(min_b.0),
+                        min_a.1.min(min_b.1),
+                        min_a.2.min(min_b.2),
+                    ),
+                    (
+                        max_a.0.max(max_b.0),
+                        max_a.1.max(max_b.1),
+                        max_a.2.max(max_b.2),
+                    ),
+                )
+            })
+    }
+
+    /// `connected_rooms` for every registered room at once, keyed by room id
+    /// Doors stay one-way, so a room can list a neighbour that doesn't list it back
+    pub fn adjacency_matrix(&self) -> HashMap<RoomId, Vec<RoomId>> {
+        self.rooms
+            .iter()
+            .map(|room| (room.room_id, self.connected_rooms(room.room_id)))
+            .collect()
+    }
+
+    /// `connected_rooms` with the edge leading to each. A door wins over a shared face,
+    /// and of several doors into one room the one at the lowest grid index is used.
+    fn connections(&self, room_id: RoomId) -> Vec<(RoomId, Edge)> {
+        let Some(room) = self.rooms.iter().find(|r| r.room_id == room_id) else {
+            return Vec::new();
+        };
+
+        let mut doors: Vec<(GridIndex, RoomId, PaletteIndex)> = room
+            .tiles
+            .iter()
+            .filter_map(|(index, tile)| match tile {
+                TileData::Door(palette, target) => Some((*index, *target, *palette)),
+                _ => None,
+            })
+            .collect();
+        doors.sort_by_key(|(index, _, _)| *index);
+
+        let mut connections: Vec<(RoomId, Edge)> = Vec::new();
+        for (_, target, palette) in doors {
+            if target != room_id
+                && self.rooms.iter().any(|r| r.room_id == target)
+                && !connections.iter().any(|(id, _)| *id == target)
+            {
+                connections.push((target, Edge::Door { palette }));
+            }
+        }
+        for position in self.positions_near(room) {
+            let other = &self.rooms[position];
+            if other.room_id == room_id || connections.iter().any(|(id, _)| *id == other.room_id) {
+                continue;
+            }
+            if let Some(face) = Room::touching_face_wrapped(room, other, &self.world_wrap) {
+                connections.push((other.room_id, Edge::Adjacent { face }));
+            }
+        }
+        connections.sort_by_key(|(id, _)| *id);
+        connections
+    }
 
+    /// Shortest route by room count from `from` to `to`, including both ends
+    /// Doors are one-way, so the reverse route may differ or not exist
+    pub fn find_path(&self, from: RoomId, to: RoomId) -> Option<Vec<RoomId>> {
+        let exists = |id: RoomId| self.rooms.iter().any(|r| r.room_id == id);
+        if !exists(from) || !exists(to) {
+            return None;
+        }
+
+        let mut previous: HashMap<RoomId, RoomId> = HashMap::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                let mut step = to;
+                while let Some(&prev) = previous.get(&step) {
+                    path.push(prev);
+                    step = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.connected_rooms(current) {
+                if visited.insert(next) {
+                    previous.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Groups of rooms joined by shared faces or doors, each sorted and ordered by their
+    /// lowest room id. Doors count both ways here, so a one-way drop doesn't split a group.
+    /// A well-formed island has exactly one.
+    pub fn connected_components(&self) -> Vec<Vec<RoomId>> {
+        let mut links: HashMap<RoomId, Vec<RoomId>> = HashMap::new();
+        for room in &self.rooms {
+            links.entry(room.room_id).or_default();
+            for target in self.connected_rooms(room.room_id) {
+                links.entry(room.room_id).or_default().push(target);
+                links.entry(target).or_default().push(room.room_id);
+            }
+        }
+
+        let mut room_ids: Vec<RoomId> = links.keys().copied().collect();
+        room_ids.sort();
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
+        for start in room_ids {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(current) = queue.pop_front() {
+                for &next in &links[&current] {
+                    if visited.insert(next) {
+                        component.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+
+    /// `find_path` with how each room is entered, for telling doors from open passages
+    /// The starting room isn't listed, so a path from a room to itself is empty
+    pub fn find_path_detailed(&self, from: RoomId, to: RoomId) -> Option<Vec<PathStep>> {
+        let exists = |id: RoomId| self.rooms.iter().any(|r| r.room_id == id);
+        if !exists(from) || !exists(to) {
+            return None;
+        }
+
+        let mut previous: HashMap<RoomId, (RoomId, Edge)> = HashMap::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut steps = Vec::new();
+                let mut step = to;
+                while let Some(&(prev, via)) = previous.get(&step) {
+                    steps.push(PathStep { room_id: step, via });
+                    step = prev;
+                }
+                steps.reverse();
+                return Some(steps);
+            }
+            for (next, via) in self.connections(current) {
+                if visited.insert(next) {
+                    previous.insert(next, (current, via));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Cheapest tile-level route from `from` to `to`, with its total cost
+    /// Entering a tile costs its palette's entry in `tile_costs` (default 1, negative is
+    /// impassable); steps go between in-room neighbors, across shared faces (and world wrap
+    /// seams) into the touching room, and through doors onto the target room's tile nearest
+    /// the door
+    pub fn find_path_weighted(
+        &self,
+        from: TileLocation,
+        to: TileLocation,
+    ) -> Option<(Vec<TileLocation>, f64)> {
+        let valid = |(room_id, index): TileLocation| {
+            self.rooms
+                .iter()
+                .any(|r| r.room_id == room_id && r.index_to_world(index).is_some())
+        };
+        if !valid(from) || !valid(to) {
+            return None;
+        }
+
+        let mut best: HashMap<TileLocation, f64> = HashMap::from([(from, 0.0)]);
+        let mut previous: HashMap<TileLocation, TileLocation> = HashMap::new();
+        let mut frontier = BinaryHeap::from([Frontier {
+            cost: 0.0,
+            location: from,
+        }]);
+        while let Some(Frontier { cost, location }) = frontier.pop() {
+            if location == to {
+                let mut path = vec![to];
+                let mut step = to;
+                while let Some(&prev) = previous.get(&step) {
+                    path.push(prev);
+                    step = prev;
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+            if best.get(&location).is_some_and(|&known| cost > known) {
+                continue;
+            }
+            for next in self.tile_edges(location) {
+                let step_cost = self.tile_cost(next);
+                if step_cost < 0.0 {
+                    continue;
+                }
+                let next_cost = cost + step_cost;
+                if best.get(&next).is_none_or(|&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    previous.insert(next, location);
+                    frontier.push(Frontier {
+                        cost: next_cost,
+                        location: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Tiles reachable in one step from `location` for `find_path_weighted`
+    fn tile_edges(&self, (room_id, index): TileLocation) -> Vec<TileLocation> {
+        let Some(room) = self.rooms.iter().find(|r| r.room_id == room_id) else {
+            return Vec::new();
+        };
+        let Some((x, y, z)) = room.index_to_world(index) else {
+            return Vec::new();
+        };
+
+        let mut edges: Vec<TileLocation> = room
+            .neighbors(index)
+            .into_iter()
+            .map(|neighbor| (room_id, neighbor))
+            .collect();
+
+        // Stepping off the room's boundary lands in whichever room covers that cell
+        let steps = [
+            (-1, 0, 0),
+            (1, 0, 0),
+            (0, -1, 0),
+            (0, 1, 0),
+            (0, 0, -1),
+            (0, 0, 1),
+        ];
+        for (dx, dy, dz) in steps {
+            let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+            if room.world_to_index(nx, ny, nz).is_some() {
+                continue;
+            }
+            for (wx, wy, wz) in self.world_wrap.offsets() {
+                let (px, py, pz) = (nx + wx, ny + wy, nz + wz);
+                for position in self.positions_near_point(px, py, pz) {
+                    let other = &self.rooms[position];
+                    if other.room_id == room_id {
+                        continue;
+                    }
+                    if let Some(other_index) = other.world_to_index(px, py, pz)
+                        && !edges.contains(&(other.room_id, other_index))
+                    {
+                        edges.push((other.room_id, other_index));
+                    }
+                }
+            }
+        }
+
+        if let Some(TileData::Door(_, target)) = room.tiles.get(&index)
+            && let Some(target_room) = self.rooms.iter().find(|r| r.room_id == *target)
+        {
+            edges.push((target_room.room_id, target_room.nearest_index(x, y, z)));
+        }
+        edges
+    }
+
+    fn tile_cost(&self, (room_id, index): TileLocation) -> f64 {
+        let tile = self
+            .rooms
+            .iter()
+            .find(|r| r.room_id == room_id)
+            .and_then(|room| room.tiles.get(&index));
+        match tile {
+            Some(TileData::Tile(palette, _)) | Some(TileData::Door(palette, _)) => {
+                self.tile_costs.get(palette).copied().unwrap_or(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Shared face area between two registered rooms, 0 if either is missing or they don't touch.
+    /// Rooms meeting across a seam of `world_wrap` share the face they meet on.
+    pub fn shared_face_area(&self, room_a_id: RoomId, room_b_id: RoomId) -> u64 {
         let room_a = self.rooms.iter().find(|r| r.room_id == room_a_id);
         let room_b = self.rooms.iter().find(|r| r.room_id == room_b_id);
 
         match (room_a, room_b) {
-            (Some(a), Some(b)) => Room::are_adjacent(a, b),
-            _ => false,
+            (Some(a), Some(b)) if room_a_id != room_b_id => {
+                Room::shared_face_area_wrapped(a, b, &self.world_wrap)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Room whose bounds contain the world point, faces included
+    /// A point on a face shared by two rooms resolves to the room with the lower position
+    pub fn room_at(&self, x: i64, y: i64, z: i64) -> Option<RoomId> {
+        self.positions_near_point(x, y, z)
+            .into_iter()
+            .map(|position| &self.rooms[position])
+            .filter(|room| room.contains_point(x, y, z))
+            .min_by_key(|room| (room.pos_x, room.pos_y, room.pos_z, room.room_id))
+            .map(|room| room.room_id)
+    }
+
+    /// Every tile with its world cell, room by room in registration order and by grid index
+    /// within a room. Tiles whose index lies outside their room's extents are skipped.
+    pub fn iter_world_tiles(&self) -> impl Iterator<Item = (i64, i64, i64, &TileData)> {
+        self.rooms.iter().flat_map(|room| {
+            let mut tiles: Vec<_> = room.tiles.iter().collect();
+            tiles.sort_by_key(|(index, _)| **index);
+            tiles.into_iter().filter_map(move |(index, tile)| {
+                room.index_to_world(*index).map(|(x, y, z)| (x, y, z, tile))
+            })
+        })
+    }
+}
+
+impl Island {
+    /// Bring an island config parsed from an older schema up to `SCHEMA_VERSION`
+    /// Every change so far only added fields with defaults; future format changes that
+    /// need real upgrades add a step here per version
+    pub fn migrate(mut self) -> Self {
+        self.version = SCHEMA_VERSION;
+        self
+    }
+
+    /// Overlay a mod's partial config, keeping base values for omitted fields
+    pub fn apply_patch(&mut self, patch: IslandPatch) {
+        if let Some(dock_room_id) = patch.dock_room_id {
+            self.dock_room_id = dock_room_id;
         }
+        if let Some(name) = patch.name {
+            self.name = name;
+        }
+        if let Some(description) = patch.description {
+            self.description = description;
+        }
+        self.metadata.extend(patch.metadata);
     }
 }
 
 impl Room {
+    /// Bring a room parsed from an older schema up to `SCHEMA_VERSION`
+    /// Rooms haven't changed shape since version 1; future format changes (such as new
+    /// `TileData` variants) add a step here per version
+    pub fn migrate(mut self) -> Self {
+        self.version = SCHEMA_VERSION;
+        self
+    }
+
+    /// World-space bounds as (min corner, max corner); max is `pos + extent` on each axis
+    pub fn aabb(&self) -> RoomBounds {
+        (
+            (self.pos_x, self.pos_y, self.pos_z),
+            (
+                self.pos_x + self.extent_x as i64,
+                self.pos_y + self.extent_y as i64,
+                self.pos_z + self.extent_z as i64,
+            ),
+        )
+    }
+
+    /// Whether a world point lies inside the room or on its boundary
+    pub fn contains_point(&self, x: i64, y: i64, z: i64) -> bool {
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = self.aabb();
+        (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) && (min_z..=max_z).contains(&z)
+    }
+
     /// Check if two rooms share a face (are physically adjacent)
     pub fn are_adjacent(a: &Room, b: &Room) -> bool {
-        let a_min_x = a.pos_x;
-        let a_max_x = a.pos_x + a.extent_x as i64;
-        let a_min_y = a.pos_y;
-        let a_max_y = a.pos_y + a.extent_y as i64;
-        let a_min_z = a.pos_z;
-        let a_max_z = a.pos_z + a.extent_z as i64;
-
-        let b_min_x = b.pos_x;
-        let b_max_x = b.pos_x + b.extent_x as i64;
-        let b_min_y = b.pos_y;
-        let b_max_y = b.pos_y + b.extent_y as i64;
-        let b_min_z = b.pos_z;
-        let b_max_z = b.pos_z + b.extent_z as i64;
+        Self::faces_touch(a.aabb(), b.aabb())
+    }
+
+    /// `are_adjacent`, also counting rooms that meet across a seam of a wrapping world
+    pub fn are_adjacent_wrapped(a: &Room, b: &Room, wrap: &WorldWrap) -> bool {
+        Self::touching_face_wrapped(a, b, wrap).is_some()
+    }
+
+    /// Face of `a` that touches `b`, looking across the seams of a wrapping world too
+    pub fn touching_face_wrapped(a: &Room, b: &Room, wrap: &WorldWrap) -> Option<Face> {
+        wrap.offsets()
+            .into_iter()
+            .find_map(|offset| Self::touching_face(a.aabb(), shift_bounds(b.aabb(), offset)))
+    }
+
+    fn faces_touch(a: RoomBounds, b: RoomBounds) -> bool {
+        Self::touching_face(a, b).is_some()
+    }
+
+    fn touching_face(a: RoomBounds, b: RoomBounds) -> Option<Face> {
+        let ((a_min_x, a_min_y, a_min_z), (a_max_x, a_max_y, a_max_z)) = a;
+        let ((b_min_x, b_min_y, b_min_z), (b_max_x, b_max_y, b_max_z)) = b;
 
         // Check if they share a face on any axis
-        let x_adjacent = (a_max_x == b_min_x || b_max_x == a_min_x)
-            && !(a_max_y <= b_min_y || b_max_y <= a_min_y)
-            && !(a_max_z <= b_min_z || b_max_z <= a_min_z);
+        let x_overlap = !(a_max_x <= b_min_x || b_max_x <= a_min_x);
+        let y_overlap = !(a_max_y <= b_min_y || b_max_y <= a_min_y);
+        let z_overlap = !(a_max_z <= b_min_z || b_max_z <= a_min_z);
+
+        if y_overlap && z_overlap {
+            if a_max_x == b_min_x {
+                return Some(Face::PosX);
+            }
+            if b_max_x == a_min_x {
+                return Some(Face::NegX);
+            }
+        }
+        if x_overlap && z_overlap {
+            if a_max_y == b_min_y {
+                return Some(Face::PosY);
+            }
+            if b_max_y == a_min_y {
+                return Some(Face::NegY);
+            }
+        }
+        if x_overlap && y_overlap {
+            if a_max_z == b_min_z {
+                return Some(Face::PosZ);
+            }
+            if b_max_z == a_min_z {
+                return Some(Face::NegZ);
+            }
+        }
+        None
+    }
+
+    /// Area of the face two rooms share, i.e. the overlap of the two axes other than the
+    /// contact axis. 0 when the rooms aren't face-adjacent.
+    pub fn shared_face_area(a: &Room, b: &Room) -> u64 {
+        Self::face_area(a.aabb(), b.aabb())
+    }
+
+    /// `shared_face_area`, also counting a face the rooms meet on across a seam of a
+    /// wrapping world, the same one `touching_face_wrapped` finds
+    pub fn shared_face_area_wrapped(a: &Room, b: &Room, wrap: &WorldWrap) -> u64 {
+        wrap.offsets()
+            .into_iter()
+            .map(|offset| Self::face_area(a.aabb(), shift_bounds(b.aabb(), offset)))
+            .find(|area| *area > 0)
+            .unwrap_or(0)
+    }
+
+    fn face_area(a: RoomBounds, b: RoomBounds) -> u64 {
+        let (a_min, a_max) = a;
+        let (b_min, b_max) = b;
+        let a_min = [a_min.0, a_min.1, a_min.2];
+        let a_max = [a_max.0, a_max.1, a_max.2];
+        let b_min = [b_min.0, b_min.1, b_min.2];
+        let b_max = [b_max.0, b_max.1, b_max.2];
+        let overlap = |axis: usize| {
+            (a_max[axis].min(b_max[axis]) - a_min[axis].max(b_min[axis])).max(0) as u64
+        };
 
-        let y_adjacent = (a_max_y == b_min_y || b_max_y == a_min_y)
-            && !(a_max_x <= b_min_x || b_max_x <= a_min_x)
-            && !(a_max_z <= b_min_z || b_max_z <= a_min_z);
+        for axis in 0..3 {
+            if a_max[axis] == b_min[axis] || b_max[axis] == a_min[axis] {
+                let area = overlap((axis + 1) % 3) * overlap((axis + 2) % 3);
+                if area > 0 {
+                    return area;
+                }
+            }
+        }
+        0
+    }
+
+    /// Check if two rooms share volume (touching faces does not count as overlap)
+    pub fn overlaps(a: &Room, b: &Room) -> bool {
+        let ((a_min_x, a_min_y, a_min_z), (a_max_x, a_max_y, a_max_z)) = a.aabb();
+        let ((b_min_x, b_min_y, b_min_z), (b_max_x, b_max_y, b_max_z)) = b.aabb();
+
+        let x_overlap = a_min_x < b_max_x && b_min_x < a_max_x;
+        let y_overlap = a_min_y < b_max_y && b_min_y < a_max_y;
+        let z_overlap = a_min_z < b_max_z && b_min_z < a_max_z;
 
-        let z_adjacent = (a_max_z == b_min_z || b_max_z == a_min_z)
-            && !(a_max_x <= b_min_x || b_max_x <= a_min_x)
-            && !(a_max_y <= b_min_y || b_max_y <= a_min_y);
+        x_overlap && y_overlap && z_overlap
+    }
 
-        x_adjacent || y_adjacent || z_adjacent
+    /// Fails with the room id and every tile index past `extent_x * extent_y * extent_z`
+    pub fn check_tile_bounds(&self) -> Result<(), String> {
+        let volume = self.grid_size();
+        let mut out_of_bounds: Vec<GridIndex> = self
+            .tiles
+            .keys()
+            .copied()
+            .filter(|&index| index >= volume)
+            .collect();
+        if out_of_bounds.is_empty() {
+            return Ok(());
+        }
+        out_of_bounds.sort_unstable();
+        let indices: Vec<String> = out_of_bounds.iter().map(|i| i.to_string()).collect();
+        Err(format!(
+            "room {} has tile indices outside its {}x{}x{} extents: {}",
+            self.room_id,
+            self.extent_x,
+            self.extent_y,
+            self.extent_z,
+            indices.join(", ")
+        ))
+    }
+
+    /// Grid indices of every tile matching `predicate`, in ascending order
+    pub fn tiles_of_type(&self, predicate: impl Fn(&TileData) -> bool) -> Vec<GridIndex> {
+        let mut indices: Vec<GridIndex> = self
+            .tiles
+            .iter()
+            .filter(|(_, tile)| predicate(tile))
+            .map(|(index, _)| *index)
+            .collect();
+        indices.sort();
+        indices
+    }
+
+    /// World cell of a grid index, with each tile one world unit wide; `None` outside the room
+    pub fn index_to_world(&self, index: GridIndex) -> Option<(i64, i64, i64)> {
+        let [x, y, z] = self.index_to_pos(index)?;
+        Some((
+            self.pos_x + x as i64,
+            self.pos_y + y as i64,
+            self.pos_z + z as i64,
+        ))
+    }
+
+    /// Grid index of the tile covering a world cell, if the cell is inside this room
+    pub fn world_to_index(&self, x: i64, y: i64, z: i64) -> Option<GridIndex> {
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = self.aabb();
+        if !((min_x..max_x).contains(&x)
+            && (min_y..max_y).contains(&y)
+            && (min_z..max_z).contains(&z))
+        {
+            return None;
+        }
+        Some(self.pos_to_index([
+            (x - min_x) as usize,
+            (y - min_y) as usize,
+            (z - min_z) as usize,
+        ]))
+    }
+
+    /// Grid index of the tile closest to a world cell, clamping it into the room
+    pub fn nearest_index(&self, x: i64, y: i64, z: i64) -> GridIndex {
+        let ((min_x, min_y, min_z), (max_x, max_y, max_z)) = self.aabb();
+        self.world_to_index(
+            x.clamp(min_x, (max_x - 1).max(min_x)),
+            y.clamp(min_y, (max_y - 1).max(min_y)),
+            z.clamp(min_z, (max_z - 1).max(min_z)),
+        )
+        .unwrap_or(0)
+    }
+
+    /// Face neighbors of a tile inside this room, wrapping around on looping axes.
+    /// Sorted, and empty for an index outside the room.
+    pub fn neighbors(&self, index: GridIndex) -> Vec<GridIndex> {
+        let Some(pos) = self.index_to_pos(index) else {
+            return Vec::new();
+        };
+        let size = self.size();
+        let looping = [self.looping_x, self.looping_y, self.looping_z];
+
+        let mut neighbors = Vec::new();
+        for axis in 0..3 {
+            let backward = match pos[axis] {
+                0 if looping[axis] => Some(size[axis] - 1),
+                0 => None,
+                p => Some(p - 1),
+            };
+            let forward = match pos[axis] + 1 {
+                p if p < size[axis] => Some(p),
+                _ if looping[axis] => Some(0),
+                _ => None,
+            };
+            for coordinate in [backward, forward].into_iter().flatten() {
+                let mut neighbor = pos;
+                neighbor[axis] = coordinate;
+                neighbors.push(self.pos_to_index(neighbor));
+            }
+        }
+        neighbors.retain(|neighbor| *neighbor != index);
+        neighbors.sort();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Whether the tile lies on the first or last layer along an axis this room loops on
+    pub fn is_on_looping_face(&self, index: GridIndex) -> bool {
+        let Some(pos) = self.index_to_pos(index) else {
+            return false;
+        };
+        let size = self.size();
+        let looping = [self.looping_x, self.looping_y, self.looping_z];
+        (0..3).any(|axis| looping[axis] && (pos[axis] == 0 || pos[axis] == size[axis] - 1))
+    }
+
+    /// Number of cells in the grid `create_grid` builds for this room
+    pub fn grid_size(&self) -> usize {
+        self.size().iter().product()
+    }
+
+    /// Extents as grid dimensions, x first
+    fn size(&self) -> [usize; 3] {
+        [
+            self.extent_x as usize,
+            self.extent_y as usize,
+            self.extent_z as usize,
+        ]
+    }
+
+    /// Grid coordinates of an index in the `CartesianGrid` layout from `create_grid`,
+    /// index = x + y*sx + z*sx*sy; `None` outside the room
+    fn index_to_pos(&self, index: GridIndex) -> Option<[usize; 3]> {
+        if index >= self.grid_size() {
+            return None;
+        }
+        let [sx, sy, _] = self.size();
+        Some([index % sx, (index / sx) % sy, index / (sx * sy)])
+    }
+
+    /// Inverse of `index_to_pos`, for coordinates inside the room
+    fn pos_to_index(&self, [x, y, z]: [usize; 3]) -> GridIndex {
+        let [sx, sy, _] = self.size();
+        x + y * sx + z * sx * sy
     }
 
     pub fn create_grid(&self) -> GridData<Cartesian3D, TileData, CartesianGrid<Cartesian3D>> {
@@ -143,18 +1371,21 @@ mod tests {
 
     fn create_test_island() -> Island {
         Island {
+            version: SCHEMA_VERSION,
             dock_room_id: 1,
             name: "Test Island".to_string(),
             description: "A test island".to_string(),
+            metadata: HashMap::new(),
         }
     }
 
     fn create_test_room() -> Room {
         let mut tiles = HashMap::new();
-        tiles.insert(0, TileData::Tile(0));
-        tiles.insert(1, TileData::Tile(1));
+        tiles.insert(0, TileData::Tile(0, Orientation::North));
+        tiles.insert(1, TileData::Tile(1, Orientation::East));
 
         Room {
+            version: SCHEMA_VERSION,
             room_id: 1,
             pos_x: 0,
             pos_y: 0,
@@ -166,7 +1397,463 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles,
+            tags: Vec::new(),
+        }
+    }
+
+    /// 1,000 rooms on a 10x10x10 lattice with uneven sizes, so some touch their neighbors and
+    /// some leave gaps, plus one slab too large for the index cells lying against the lattice
+    fn create_large_island() -> IslandData {
+        let mut rooms = Vec::new();
+        for i in 0..1000 {
+            let (lx, ly, lz) = (i % 10, (i / 10) % 10, i / 100);
+            rooms.push(Room {
+                room_id: i as RoomId + 1,
+                pos_x: lx * 6,
+                pos_y: ly * 6,
+                pos_z: lz * 6,
+                extent_x: (3 + (i * 7) % 4) as u32,
+                extent_y: (3 + (i * 5) % 4) as u32,
+                extent_z: (3 + (i * 3) % 4) as u32,
+                tiles: HashMap::new(),
+                ..create_test_room()
+            });
         }
+        rooms.push(Room {
+            room_id: 5000,
+            pos_x: -90,
+            pos_y: 0,
+            pos_z: 0,
+            extent_x: 90,
+            extent_y: 40,
+            extent_z: 40,
+            tiles: HashMap::new(),
+            ..create_test_room()
+        });
+        IslandData::new(create_test_island(), rooms)
+    }
+
+    #[test]
+    fn test_spatial_index_matches_naive_scan() {
+        let indexed = create_large_island();
+        assert!(!indexed.spatial_index().oversized.is_empty());
+        // Listing every room as oversized turns each lookup into a full scan
+        let mut naive = indexed.clone();
+        naive.spatial_index = OnceLock::from(RoomGridIndex {
+            cell_size: 1,
+            cells: HashMap::new(),
+            oversized: (0..naive.rooms.len()).collect(),
+        });
+
+        for x in (-95..65).step_by(4) {
+            for y in (-3..65).step_by(4) {
+                for z in (-3..65).step_by(5) {
+                    assert_eq!(
+                        indexed.room_at(x, y, z),
+                        naive.room_at(x, y, z),
+                        "room_at({}, {}, {})",
+                        x,
+                        y,
+                        z
+                    );
+                }
+            }
+        }
+
+        let adjacency = naive.to_json_value()["adjacency"].clone();
+        assert!(!adjacency.as_array().unwrap().is_empty());
+        assert_eq!(indexed.to_json_value()["adjacency"], adjacency);
+        for room in &naive.rooms {
+            assert_eq!(
+                indexed.connected_rooms(room.room_id),
+                naive.connected_rooms(room.room_id),
+                "connected_rooms({})",
+                room.room_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_rooms_mut_keeps_spatial_index_current() {
+        let room_a = create_test_room();
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 10;
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+        assert_eq!(island_data.room_at(11, 1, 1), Some(2));
+
+        island_data.rooms_mut()[1].pos_x = 20;
+        assert_eq!(island_data.room_at(11, 1, 1), None);
+        assert_eq!(island_data.room_at(21, 1, 1), Some(2));
+    }
+
+    #[test]
+    fn test_iter_world_tiles_offsets_by_room_position() {
+        let room_a = create_test_room();
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.pos_z = -2;
+        room_b.tiles.clear();
+        // x=1, y=2, z=1 inside the 3x3x3 grid
+        room_b.tiles.insert(1 + 2 * 3 + 9, TileData::Door(4, 1));
+        room_b
+            .tiles
+            .insert(27, TileData::Tile(9, Orientation::North));
+        let island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+
+        let tiles: Vec<_> = island_data.iter_world_tiles().collect();
+        assert_eq!(
+            tiles,
+            vec![
+                (0, 0, 0, &TileData::Tile(0, Orientation::North)),
+                (1, 0, 0, &TileData::Tile(1, Orientation::East)),
+                (4, 2, -1, &TileData::Door(4, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_path_through_adjacency_and_doors() {
+        let room_a = create_test_room();
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.tiles.insert(4, TileData::Door(0, 3));
+        let mut room_c = create_test_room();
+        room_c.room_id = 3;
+        room_c.pos_x = 20;
+
+        let island_data = IslandData::new(create_test_island(), vec![room_a, room_b, room_c]);
+
+        assert_eq!(island_data.connected_rooms(2), vec![1, 3]);
+        assert_eq!(island_data.find_path(1, 3), Some(vec![1, 2, 3]));
+        assert_eq!(island_data.find_path(1, 1), Some(vec![1]));
+        // The door into room 3 is one-way and room 3 touches nothing
+        assert_eq!(island_data.find_path(3, 1), None);
+        assert_eq!(island_data.find_path(1, 99), None);
+    }
+
+    #[test]
+    fn test_adjacency_matrix() {
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(4, TileData::Door(0, 3));
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let mut room_c = create_test_room();
+        room_c.room_id = 3;
+        room_c.pos_x = 20;
+
+        let island_data = IslandData::new(create_test_island(), vec![room_c, room_a, room_b]);
+        let matrix = island_data.adjacency_matrix();
+        assert_eq!(matrix.len(), 3);
+        assert_eq!(matrix[&1], vec![2, 3]);
+        assert_eq!(matrix[&2], vec![1]);
+        assert!(matrix[&3].is_empty(), "The door into 3 is one-way");
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(4, TileData::Door(0, 5));
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let mut room_c = create_test_room();
+        room_c.room_id = 3;
+        room_c.pos_x = 20;
+        let mut room_d = create_test_room();
+        room_d.room_id = 4;
+        room_d.pos_x = 23;
+        let mut room_e = create_test_room();
+        room_e.room_id = 5;
+        room_e.pos_x = 40;
+        let mut room_f = create_test_room();
+        room_f.room_id = 6;
+        room_f.pos_x = 60;
+
+        // 1-2 touch and 1 has a one-way door to 5; 3-4 touch; 6 stands alone
+        let island_data = IslandData::new(
+            create_test_island(),
+            vec![room_d, room_f, room_a, room_c, room_b, room_e],
+        );
+        assert_eq!(
+            island_data.connected_components(),
+            vec![vec![1, 2, 5], vec![3, 4], vec![6]]
+        );
+
+        let whole = IslandData::new(create_test_island(), vec![create_test_room()]);
+        assert_eq!(whole.connected_components(), vec![vec![1]]);
+        let empty = IslandData::new(create_test_island(), Vec::new());
+        assert!(empty.connected_components().is_empty());
+    }
+
+    #[test]
+    fn test_find_path_detailed_names_each_edge() {
+        let room_a = create_test_room();
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.tiles.insert(4, TileData::Door(7, 3));
+        let mut room_c = create_test_room();
+        room_c.room_id = 3;
+        room_c.pos_x = 20;
+        let mut room_d = create_test_room();
+        room_d.room_id = 4;
+        room_d.pos_y = -3;
+
+        let island_data =
+            IslandData::new(create_test_island(), vec![room_a, room_b, room_c, room_d]);
+
+        assert_eq!(
+            island_data.find_path_detailed(1, 3),
+            Some(vec![
+                PathStep {
+                    room_id: 2,
+                    via: Edge::Adjacent { face: Face::PosX },
+                },
+                PathStep {
+                    room_id: 3,
+                    via: Edge::Door { palette: 7 },
+                },
+            ])
+        );
+        assert_eq!(
+            island_data.find_path_detailed(2, 4),
+            Some(vec![
+                PathStep {
+                    room_id: 1,
+                    via: Edge::Adjacent { face: Face::NegX },
+                },
+                PathStep {
+                    room_id: 4,
+                    via: Edge::Adjacent { face: Face::NegY },
+                },
+            ])
+        );
+        assert_eq!(island_data.find_path_detailed(1, 1), Some(Vec::new()));
+        assert_eq!(island_data.find_path_detailed(3, 1), None);
+        assert_eq!(island_data.find_path_detailed(1, 99), None);
+    }
+
+    #[test]
+    fn test_find_path_detailed_prefers_door_over_shared_face() {
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(2, TileData::Door(9, 2));
+        room_a.tiles.insert(5, TileData::Door(3, 2));
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+
+        let via = |island_data: &IslandData, from, to| {
+            island_data.find_path_detailed(from, to).unwrap()[0].via
+        };
+        assert_eq!(via(&island_data, 1, 2), Edge::Door { palette: 9 });
+        assert_eq!(via(&island_data, 2, 1), Edge::Adjacent { face: Face::NegX });
+
+        // Across the seam room 2's far side meets room 1's near side
+        island_data.rooms_mut()[0].tiles.clear();
+        island_data.rooms_mut()[1].pos_x = 10;
+        island_data.world_wrap = WorldWrap {
+            x: Some(13),
+            ..Default::default()
+        };
+        assert_eq!(via(&island_data, 1, 2), Edge::Adjacent { face: Face::NegX });
+        assert_eq!(via(&island_data, 2, 1), Edge::Adjacent { face: Face::PosX });
+    }
+
+    #[test]
+    fn test_room_at_prefers_lower_room_on_shared_face() {
+        let room_a = create_test_room();
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let island_data = IslandData::new(create_test_island(), vec![room_b, room_a]);
+
+        assert_eq!(island_data.room_at(1, 1, 1), Some(1));
+        assert_eq!(island_data.room_at(3, 1, 1), Some(1));
+        assert_eq!(island_data.room_at(4, 1, 1), Some(2));
+        assert_eq!(island_data.room_at(10, 0, 0), None);
+        assert_eq!(island_data.room_at(1, -1, 1), None);
+    }
+
+    #[test]
+    fn test_shared_face_area() {
+        let room_a = create_test_room();
+
+        let mut flush = create_test_room();
+        flush.room_id = 2;
+        flush.pos_x = 3;
+        assert_eq!(Room::shared_face_area(&room_a, &flush), 9);
+
+        let mut offset = create_test_room();
+        offset.room_id = 3;
+        offset.pos_y = 3;
+        offset.pos_x = 1;
+        offset.pos_z = 2;
+        assert_eq!(Room::shared_face_area(&room_a, &offset), 2);
+
+        let mut edge_only = create_test_room();
+        edge_only.room_id = 4;
+        edge_only.pos_x = 3;
+        edge_only.pos_y = 3;
+        assert_eq!(Room::shared_face_area(&room_a, &edge_only), 0);
+
+        let mut beyond = create_test_room();
+        beyond.room_id = 5;
+        beyond.pos_x = 6;
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, flush, beyond]);
+        assert_eq!(island_data.shared_face_area(1, 2), 9);
+        assert_eq!(island_data.shared_face_area(1, 99), 0);
+        assert_eq!(island_data.shared_face_area(1, 5), 0);
+
+        // Room 5 ends at x = 9, which wraps onto room 1's face at x = 0
+        island_data.world_wrap = WorldWrap {
+            x: Some(9),
+            ..Default::default()
+        };
+        assert_eq!(island_data.shared_face_area(1, 5), 9);
+        assert_eq!(island_data.shared_face_area(5, 1), 9);
+    }
+
+    #[test]
+    fn test_room_aabb() {
+        let mut room = create_test_room();
+        room.pos_x = -2;
+        room.pos_y = 4;
+        room.extent_z = 7;
+        assert_eq!(room.aabb(), ((-2, 4, 0), (1, 7, 7)));
+    }
+
+    #[test]
+    fn test_world_bounds() {
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = -10;
+        room_b.pos_z = 4;
+        room_b.extent_y = 8;
+        let island_data = IslandData::new(create_test_island(), vec![create_test_room(), room_b]);
+        assert_eq!(island_data.world_bounds(), Some(((-10, 0, 0), (3, 8, 7))));
+
+        let empty = IslandData::new(create_test_island(), Vec::new());
+        assert_eq!(empty.world_bounds(), None);
+    }
+
+    #[test]
+    fn test_neighbors_respect_looping() {
+        let mut room = create_test_room();
+        // Corner (0, 0, 0) of a 3x3x3 room
+        assert_eq!(room.neighbors(0), vec![1, 3, 9]);
+        // Center (1, 1, 1) touches all six faces
+        assert_eq!(room.neighbors(13), vec![4, 10, 12, 14, 16, 22]);
+        assert!(room.neighbors(27).is_empty());
+
+        room.looping_x = true;
+        assert_eq!(room.neighbors(0), vec![1, 2, 3, 9]);
+    }
+
+    /// Room `room_id` at x = `pos_x`, 3 wide, 2 deep and 1 high
+    fn create_flat_room(room_id: RoomId, pos_x: i64) -> Room {
+        Room {
+            room_id,
+            pos_x,
+            extent_y: 2,
+            extent_z: 1,
+            tiles: HashMap::new(),
+            ..create_test_room()
+        }
+    }
+
+    #[test]
+    fn test_find_path_weighted_avoids_costly_tiles() {
+        // Lava (palette 5) at (1, 0) of room 1; the cheap route detours through y = 1
+        let mut room_a = create_flat_room(1, 0);
+        room_a
+            .tiles
+            .insert(1, TileData::Tile(5, Orientation::North));
+        let room_b = create_flat_room(2, 3);
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+        island_data.tile_costs = HashMap::from([(5, 10.0)]);
+
+        let (path, cost) = island_data.find_path_weighted((1, 0), (2, 0)).unwrap();
+        assert_eq!(cost, 5.0);
+        assert_eq!(path.first(), Some(&(1, 0)));
+        assert_eq!(path.last(), Some(&(2, 0)));
+        assert!(!path.contains(&(1, 1)));
+
+        // Walling off the detour too leaves no passable route
+        island_data.rooms_mut()[0]
+            .tiles
+            .insert(4, TileData::Tile(5, Orientation::North));
+        island_data.tile_costs.insert(5, IMPASSABLE_COST);
+        assert_eq!(island_data.find_path_weighted((1, 0), (2, 0)), None);
+
+        // Costs are island data, so they survive a save and reload
+        let reloaded = IslandData::from_ron(&island_data.to_ron()).unwrap();
+        assert_eq!(reloaded.tile_costs, island_data.tile_costs);
+        let reloaded = IslandData::from_bincode(&island_data.to_bincode()).unwrap();
+        assert_eq!(reloaded.tile_costs, island_data.tile_costs);
+    }
+
+    #[test]
+    fn test_find_path_weighted_through_door() {
+        let mut room_a = create_flat_room(1, 0);
+        room_a.tiles.insert(2, TileData::Door(0, 2));
+        let room_b = create_flat_room(2, 50);
+        let island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+
+        let (path, cost) = island_data.find_path_weighted((1, 0), (2, 3)).unwrap();
+        // Two steps to the door, land on (50, 0) nearest it, then one step to (50, 1)
+        assert_eq!(path, vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 3)]);
+        assert_eq!(cost, 4.0);
+        assert_eq!(island_data.find_path_weighted((1, 0), (2, 99)), None);
+    }
+
+    #[test]
+    fn test_find_path_weighted_across_world_wrap_seam() {
+        let room_a = create_flat_room(1, 0);
+        let room_b = create_flat_room(2, 5);
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+        assert_eq!(island_data.find_path_weighted((1, 0), (2, 2)), None);
+
+        // Stepping off x = 0 lands on x = 7, the last column of room 2
+        island_data.world_wrap = WorldWrap {
+            x: Some(8),
+            ..Default::default()
+        };
+        let (path, cost) = island_data.find_path_weighted((1, 0), (2, 2)).unwrap();
+        assert_eq!(path, vec![(1, 0), (2, 2)]);
+        assert_eq!(cost, 1.0);
+    }
+
+    #[test]
+    fn test_tiles_of_type() {
+        let mut room = create_test_room();
+        room.tiles.insert(5, TileData::Door(2, 7));
+        room.tiles.insert(2, TileData::Tile(1, Orientation::South));
+
+        let doors = room.tiles_of_type(|tile| matches!(tile, TileData::Door(_, _)));
+        assert_eq!(doors, vec![5]);
+
+        let palette_one = room.tiles_of_type(|tile| matches!(tile, TileData::Tile(1, _)));
+        assert_eq!(palette_one, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_check_tile_bounds() {
+        let mut room = create_test_room();
+        assert!(room.check_tile_bounds().is_ok());
+
+        room.tiles.insert(27, TileData::Door(0, 2));
+        room.tiles
+            .insert(9999, TileData::Tile(1, Orientation::North));
+        room.tiles.insert(26, TileData::Tile(1, Orientation::North));
+        assert_eq!(
+            room.check_tile_bounds().unwrap_err(),
+            "room 1 has tile indices outside its 3x3x3 extents: 27, 9999"
+        );
     }
 
     #[test]
@@ -174,11 +1861,33 @@ mod tests {
         let room = create_test_room();
         let grid_data = room.create_grid();
         assert_eq!(grid_data.grid().total_size(), 27); // 3x3x3
+        assert_eq!(room.grid_size(), 27);
+    }
+
+    #[test]
+    fn test_create_grid_places_trigger_tiles() {
+        let mut room = create_test_room();
+        room.tiles
+            .insert(4, TileData::Trigger("spike_trap".to_string()));
+        let grid_data = room.create_grid();
+        assert_eq!(
+            grid_data.get(4),
+            &TileData::Trigger("spike_trap".to_string())
+        );
+        assert_eq!(grid_data.get(0), &TileData::Tile(0, Orientation::North));
+    }
+
+    #[test]
+    fn test_create_grid_preserves_orientation() {
+        let room = create_test_room();
+        let grid_data = room.create_grid();
+        assert_eq!(grid_data.get(1), &TileData::Tile(1, Orientation::East));
     }
 
     #[test]
     fn test_room_adjacency_x_axis() {
         let room_a = Room {
+            version: SCHEMA_VERSION,
             room_id: 1,
             pos_x: 0,
             pos_y: 0,
@@ -190,9 +1899,11 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         let room_b = Room {
+            version: SCHEMA_VERSION,
             room_id: 2,
             pos_x: 5,
             pos_y: 0,
@@ -204,6 +1915,7 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         assert!(Room::are_adjacent(&room_a, &room_b));
@@ -212,6 +1924,7 @@ mod tests {
     #[test]
     fn test_room_adjacency_not_adjacent() {
         let room_a = Room {
+            version: SCHEMA_VERSION,
             room_id: 1,
             pos_x: 0,
             pos_y: 0,
@@ -223,9 +1936,11 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         let room_b = Room {
+            version: SCHEMA_VERSION,
             room_id: 2,
             pos_x: 10,
             pos_y: 0,
@@ -237,15 +1952,97 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         assert!(!Room::are_adjacent(&room_a, &room_b));
     }
 
+    #[test]
+    fn test_dangling_doors() {
+        let island = create_test_island();
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(5, TileData::Door(0, 2));
+        room_a.tiles.insert(6, TileData::Door(0, 42));
+
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+
+        let island_data = IslandData::new(island, vec![room_a, room_b]);
+        assert_eq!(island_data.dangling_doors(), vec![(1, 6, 42)]);
+    }
+
+    #[test]
+    fn test_doors_on_looping_faces() {
+        // 3x3x3 rooms: index 4 is (1, 1, 0), 13 is the center, 12 is (0, 1, 1)
+        let mut room_a = create_test_room();
+        room_a.looping_x = true;
+        room_a.tiles.insert(12, TileData::Door(0, 2));
+        room_a.tiles.insert(13, TileData::Door(0, 2));
+        room_a.tiles.insert(4, TileData::Door(0, 2));
+        room_a
+            .tiles
+            .insert(3, TileData::Tile(0, Orientation::North));
+
+        // Same doors in a room that doesn't loop are fine
+        let mut room_b = room_a.clone();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.looping_x = false;
+
+        let island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+        assert_eq!(island_data.doors_on_looping_faces(), vec![(1, 12)]);
+
+        let mut island_data = island_data;
+        island_data.rooms_mut()[0].looping_z = true;
+        assert_eq!(island_data.doors_on_looping_faces(), vec![(1, 4), (1, 12)]);
+    }
+
+    #[test]
+    fn test_unpaired_doors() {
+        let island = create_test_island();
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(5, TileData::Door(0, 2));
+        room_a.tiles.insert(6, TileData::Door(0, 3));
+        room_a.tiles.insert(7, TileData::Door(0, 3));
+
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.tiles.insert(5, TileData::Door(0, 1));
+
+        let mut room_c = create_test_room();
+        room_c.room_id = 3;
+        room_c.pos_x = 6;
+
+        let island_data = IslandData::new(island, vec![room_a, room_b, room_c]);
+        assert_eq!(island_data.unpaired_doors(), vec![(1, 3)]);
+    }
+
+    #[test]
+    fn test_room_overlaps() {
+        let room_a = create_test_room();
+
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 2;
+        room_b.pos_y = 1;
+        assert!(Room::overlaps(&room_a, &room_b));
+        assert!(Room::overlaps(&room_b, &room_a));
+
+        // Sharing a face is adjacency, not overlap
+        room_b.pos_x = 3;
+        room_b.pos_y = 0;
+        assert!(!Room::overlaps(&room_a, &room_b));
+        assert!(Room::are_adjacent(&room_a, &room_b));
+    }
+
     #[test]
     fn test_rooms_are_adjacent_through_island_data() {
         let island = create_test_island();
         let room_a = Room {
+            version: SCHEMA_VERSION,
             room_id: 1,
             pos_x: 0,
             pos_y: 0,
@@ -257,9 +2054,11 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         let room_b = Room {
+            version: SCHEMA_VERSION,
             room_id: 2,
             pos_x: 5,
             pos_y: 0,
@@ -271,6 +2070,7 @@ mod tests {
             looping_y: false,
             looping_z: false,
             tiles: HashMap::new(),
+            tags: Vec::new(),
         };
 
         let island_data = IslandData::new(island, vec![room_a, room_b]);
@@ -278,6 +2078,87 @@ mod tests {
         assert!(!island_data.rooms_are_adjacent(1, 999));
     }
 
+    #[test]
+    fn test_rooms_adjacent_across_world_wrap_seam() {
+        let mut room_a = create_test_room();
+        room_a.tiles.clear();
+        let mut room_b = room_a.clone();
+        room_b.room_id = 2;
+        room_b.pos_x = 17;
+        let mut island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+        assert!(!island_data.rooms_are_adjacent(1, 2));
+
+        island_data.world_wrap = WorldWrap {
+            x: Some(20),
+            ..Default::default()
+        };
+        assert!(island_data.rooms_are_adjacent(1, 2));
+        assert!(island_data.rooms_are_adjacent(2, 1));
+        assert_eq!(island_data.connected_rooms(1), vec![2]);
+
+        let reloaded = IslandData::from_ron(&island_data.to_ron()).unwrap();
+        assert_eq!(reloaded.world_wrap, island_data.world_wrap);
+
+        // A seam on another axis doesn't join rooms that only line up on x
+        island_data.world_wrap = WorldWrap {
+            z: Some(20),
+            ..Default::default()
+        };
+        assert!(!island_data.rooms_are_adjacent(1, 2));
+    }
+
+    #[test]
+    fn test_adjacency_reason() {
+        let mut room_far = create_test_room();
+        room_far.room_id = 3;
+        room_far.pos_x = 100;
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let island_data = IslandData::new(
+            create_test_island(),
+            vec![create_test_room(), room_b, room_far],
+        );
+
+        assert_eq!(
+            island_data.adjacency_reason(1, 2),
+            AdjacencyReason::Adjacent
+        );
+        assert_eq!(
+            island_data.adjacency_reason(1, 3),
+            AdjacencyReason::NotTouching
+        );
+        assert_eq!(
+            island_data.adjacency_reason(1, 1),
+            AdjacencyReason::SameRoom
+        );
+        assert_eq!(
+            island_data.adjacency_reason(9, 1),
+            AdjacencyReason::RoomMissing(9)
+        );
+        assert_eq!(
+            island_data.adjacency_reason(1, 9).to_string(),
+            "room_missing:9"
+        );
+        assert_eq!(AdjacencyReason::NotTouching.to_string(), "not_touching");
+    }
+
+    #[test]
+    fn test_doors_lists_every_door_edge() {
+        let island = create_test_island();
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(5, TileData::Door(7, 2));
+        room_a.tiles.insert(3, TileData::Door(4, 3));
+
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.tiles.insert(0, TileData::Door(7, 1));
+
+        let island_data = IslandData::new(island, vec![room_a, room_b]);
+        assert_eq!(island_data.doors(), vec![(1, 3, 4), (1, 2, 7), (2, 1, 7)]);
+    }
+
     #[test]
     fn test_ron_serialization_room() {
         let room = create_test_room();
@@ -287,6 +2168,32 @@ mod tests {
         assert_eq!(deserialized.tiles.len(), 2);
     }
 
+    #[test]
+    fn test_ron_serialization_trigger_tile() {
+        let mut room = create_test_room();
+        room.tiles
+            .insert(2, TileData::Trigger("cutscene_intro".to_string()));
+        let serialized = ron::to_string(&room).unwrap();
+        let deserialized: Room = ron::from_str(&serialized).unwrap();
+        assert_eq!(
+            deserialized.tiles.get(&2),
+            Some(&TileData::Trigger("cutscene_intro".to_string()))
+        );
+        assert_eq!(
+            deserialized.tiles.get(&0),
+            Some(&TileData::Tile(0, Orientation::North))
+        );
+    }
+
+    #[test]
+    fn test_ron_tile_without_orientation_defaults_to_north() {
+        let tile: TileData = ron::from_str("Tile(3)").unwrap();
+        assert_eq!(tile, TileData::Tile(3, Orientation::North));
+
+        let tile: TileData = ron::from_str("Tile(3, West)").unwrap();
+        assert_eq!(tile, TileData::Tile(3, Orientation::West));
+    }
+
     #[test]
     fn test_ron_serialization_island() {
         let island = create_test_island();
@@ -295,21 +2202,261 @@ mod tests {
         assert_eq!(deserialized.name, "Test Island");
     }
 
+    #[test]
+    fn test_ron_serialization_island_data() {
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let mut island_data =
+            IslandData::new(create_test_island(), vec![create_test_room(), room_b]);
+        island_data.entity_spawns.push(EntitySpawn {
+            id: None,
+            entity_type: "npc_basic".to_string(),
+            room_id: 2,
+            grid_index: 4,
+            properties: HashMap::new(),
+            rotation: None,
+            scale: None,
+        });
+
+        let serialized = island_data.to_ron();
+        let deserialized = IslandData::from_ron(&serialized).unwrap();
+        assert_eq!(deserialized.island.name, "Test Island");
+        assert_eq!(deserialized.rooms.len(), 2);
+        assert_eq!(deserialized.rooms[1].room_id, 2);
+        assert_eq!(deserialized.rooms[0].tiles.len(), 2);
+        assert_eq!(deserialized.entity_spawns.len(), 1);
+        assert!(deserialized.rooms_are_adjacent(1, 2));
+
+        let without_spawns = IslandData::new(create_test_island(), vec![create_test_room()]);
+        let deserialized = IslandData::from_ron(&without_spawns.to_ron()).unwrap();
+        assert!(deserialized.entity_spawns.is_empty());
+    }
+
+    #[test]
+    fn test_bincode_round_trip_matches_ron() {
+        let mut room_a = create_test_room();
+        room_a.tags = vec!["start".to_string()];
+        room_a.tiles.insert(4, TileData::Door(7, 2));
+        room_a
+            .tiles
+            .insert(5, TileData::Trigger("bell".to_string()));
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        room_b.looping_y = true;
+        room_b.tiles.insert(6, TileData::None);
+        let mut island = create_test_island();
+        island
+            .metadata
+            .insert("author".to_string(), "Xena".to_string());
+        let mut island_data = IslandData::new(island, vec![room_a, room_b]);
+        island_data.entity_spawns.push(EntitySpawn {
+            id: None,
+            entity_type: "npc_basic".to_string(),
+            room_id: 2,
+            grid_index: 4,
+            properties: HashMap::from([("health".to_string(), "100".to_string())]),
+            rotation: None,
+            scale: None,
+        });
+        island_data.world_wrap = WorldWrap {
+            z: Some(30),
+            ..Default::default()
+        };
+
+        let from_ron = IslandData::from_ron(&island_data.to_ron()).unwrap();
+        let from_bincode = IslandData::from_bincode(&island_data.to_bincode()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&from_bincode).unwrap(),
+            serde_json::to_value(&from_ron).unwrap()
+        );
+        assert_eq!(from_bincode.world_wrap, from_ron.world_wrap);
+        assert_eq!(from_bincode.room_at(4, 1, 1), Some(2));
+        assert!(from_bincode.rooms_are_adjacent(1, 2));
+
+        // Empty optional fields, which RON omits, still line up
+        let plain = IslandData::new(create_test_island(), vec![create_test_room()]);
+        let from_bincode = IslandData::from_bincode(&plain.to_bincode()).unwrap();
+        assert_eq!(from_bincode.rooms[0].tiles.len(), 2);
+        assert!(from_bincode.island.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_from_bincode_rejects_foreign_and_stale_files() {
+        let bytes = IslandData::new(create_test_island(), vec![create_test_room()]).to_bincode();
+
+        let err = IslandData::from_bincode(b"(island: ())").unwrap_err();
+        assert!(err.contains("not a binary island"), "{}", err);
+
+        for version in [SCHEMA_VERSION - 1, SCHEMA_VERSION + 1] {
+            let mut stale = bytes.clone();
+            stale[4..8].copy_from_slice(&version.to_le_bytes());
+            let err = IslandData::from_bincode(&stale).unwrap_err();
+            assert!(err.contains("re-bake it from RON"), "{}", err);
+        }
+
+        assert!(IslandData::from_bincode(&bytes[..bytes.len() - 1]).is_err());
+        let mut padded = bytes.clone();
+        padded.push(0);
+        let err = IslandData::from_bincode(&padded).unwrap_err();
+        assert!(err.contains("1 unexpected bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_to_json_includes_doors_and_adjacency() {
+        let mut room_a = create_test_room();
+        room_a.tiles.insert(4, TileData::Door(7, 2));
+        let mut room_b = create_test_room();
+        room_b.room_id = 2;
+        room_b.pos_x = 3;
+        let island_data = IslandData::new(create_test_island(), vec![room_a, room_b]);
+
+        let exported: serde_json::Value = serde_json::from_str(&island_data.to_json()).unwrap();
+        assert_eq!(exported["island"]["name"], "Test Island");
+        assert_eq!(exported["rooms"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            exported["doors"][0],
+            json!({ "from": 1, "to": 2, "palette": 7 })
+        );
+        assert_eq!(exported["adjacency"], json!([[1, 2]]));
+    }
+
+    #[test]
+    fn test_ron_room_without_looping_or_extents_uses_defaults() {
+        let room: Room = ron::from_str(
+            "(room_id: 4, pos_x: 1, pos_y: 2, pos_z: 3, extent_x: 6, tiles: {0: Tile(2)})",
+        )
+        .unwrap();
+        assert_eq!((room.extent_x, room.extent_y, room.extent_z), (6, 1, 1));
+        assert!(!room.looping_x && !room.looping_y && !room.looping_z);
+        assert_eq!(
+            room.tiles.get(&0),
+            Some(&TileData::Tile(2, Orientation::North))
+        );
+    }
+
+    #[test]
+    fn test_ron_room_tags_round_trip() {
+        let untagged: Room =
+            ron::from_str("(room_id: 1, pos_x: 0, pos_y: 0, pos_z: 0, tiles: {})").unwrap();
+        assert!(untagged.tags.is_empty());
+        assert!(!ron::to_string(&untagged).unwrap().contains("tags"));
+
+        let mut room = create_test_room();
+        room.tags = vec!["forest".to_string(), "boss".to_string()];
+        let deserialized: Room = ron::from_str(&ron::to_string(&room).unwrap()).unwrap();
+        assert_eq!(deserialized.tags, vec!["forest", "boss"]);
+    }
+
+    #[test]
+    fn test_schema_version_defaults_and_limits() {
+        let room: Room = ron::from_str(
+            "(room_id: 1, pos_x: 0, pos_y: 0, pos_z: 0, extent_x: 1, extent_y: 1, extent_z: 1, \
+             looping_x: false, looping_y: false, looping_z: false, tiles: {})",
+        )
+        .unwrap();
+        assert_eq!(room.version, SCHEMA_VERSION);
+
+        let mut old_room = create_test_room();
+        old_room.version = 0;
+        assert!(check_schema_version(old_room.version).is_ok());
+        assert_eq!(old_room.migrate().version, SCHEMA_VERSION);
+
+        let err = check_schema_version(SCHEMA_VERSION + 1).unwrap_err();
+        assert!(err.contains("newer than the supported version"));
+    }
+
+    #[test]
+    fn test_from_ron_checks_schema_versions() {
+        let mut island_data = IslandData::new(create_test_island(), vec![create_test_room()]);
+        island_data.island.version = 0;
+        island_data.rooms_mut()[0].version = 0;
+        let migrated = IslandData::from_ron(&island_data.to_ron()).unwrap();
+        assert_eq!(migrated.island.version, SCHEMA_VERSION);
+        assert_eq!(migrated.rooms()[0].version, SCHEMA_VERSION);
+
+        island_data.island.version = SCHEMA_VERSION + 1;
+        let err = IslandData::from_ron(&island_data.to_ron()).unwrap_err();
+        assert!(err.starts_with("island: schema version"));
+
+        island_data.island.version = SCHEMA_VERSION;
+        island_data.rooms_mut()[0].version = SCHEMA_VERSION + 1;
+        let err = IslandData::from_ron(&island_data.to_ron()).unwrap_err();
+        let room_id = island_data.rooms()[0].room_id;
+        assert!(err.starts_with(&format!("room {}: schema version", room_id)));
+    }
+
+    #[test]
+    fn test_island_apply_patch() {
+        let mut island = create_test_island();
+        let patch: IslandPatch = ron::from_str(r#"(description: Some("Patched"))"#).unwrap();
+        island.apply_patch(patch);
+        assert_eq!(island.dock_room_id, 1);
+        assert_eq!(island.name, "Test Island");
+        assert_eq!(island.description, "Patched");
+
+        let patch: IslandPatch = ron::from_str(r#"(metadata: {"author": "Modder"})"#).unwrap();
+        island.apply_patch(patch);
+        assert_eq!(island.description, "Patched");
+        assert_eq!(island.metadata["author"], "Modder");
+    }
+
+    #[test]
+    fn test_ron_island_metadata_is_optional() {
+        let island: Island =
+            ron::from_str(r#"(dock_room_id: 1, name: "Plain", description: "No credits")"#)
+                .unwrap();
+        assert!(island.metadata.is_empty());
+        assert!(!ron::to_string(&island).unwrap().contains("metadata:"));
+
+        let island: Island = ron::from_str(
+            r#"(
+                dock_room_id: 1,
+                name: "Credited",
+                description: "With metadata",
+                metadata: {"author": "Xena", "homepage": "https://example.com"},
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(island.metadata["author"], "Xena");
+        assert_eq!(island.metadata["homepage"], "https://example.com");
+        let reloaded: Island = ron::from_str(&ron::to_string(&island).unwrap()).unwrap();
+        assert_eq!(reloaded.metadata, island.metadata);
+    }
+
     #[test]
     fn test_ron_serialization_entity_spawn() {
         let mut properties = HashMap::new();
         properties.insert("health".to_string(), "100".to_string());
 
         let spawn = EntitySpawn {
+            id: Some("guard_1".to_string()),
             entity_type: "npc_basic".to_string(),
             room_id: 1,
             grid_index: 5,
             properties,
+            rotation: Some(90.0),
+            scale: Some(1.5),
         };
 
         let serialized = ron::to_string(&spawn).unwrap();
         let deserialized: EntitySpawn = ron::from_str(&serialized).unwrap();
         assert_eq!(deserialized.entity_type, "npc_basic");
         assert_eq!(deserialized.properties.get("health").unwrap(), "100");
+        assert_eq!(deserialized.id.as_deref(), Some("guard_1"));
+        assert_eq!(deserialized.rotation, Some(90.0));
+        assert_eq!(deserialized.scale, Some(1.5));
+    }
+
+    #[test]
+    fn test_spawn_without_rotation_or_scale_still_parses() {
+        let spawn: EntitySpawn = ron::from_str(
+            r#"(entity_type: "npc_basic", room_id: 1, grid_index: 5, properties: {})"#,
+        )
+        .unwrap();
+        assert_eq!(spawn.id, None);
+        assert_eq!(spawn.rotation, None);
+        assert_eq!(spawn.scale, None);
     }
 }
@@ -1,44 +1,80 @@
+use crate::protocol::{
+    ChunkReassembler, IslandReplicationLogEntry, LOG_SEND_INTERVAL, LatencyTracker, LogOutbox,
+    MAX_LOG_ENTRY_LEN, MAX_RECONNECT_ATTEMPTS, NetMessage, RECV_BURST,
+    RECV_ERRORS_BEFORE_RECONNECT, RECV_RATE_PER_SEC, ReplicationLogReceiver, SenderRateLimiter,
+    SocketStats, SocketStatsSnapshot, Throttle, log_entry_datagrams, parse_public_ip_response,
+    reconnect_delay,
+};
 use godot::classes::enet_connection::CompressionMode;
 use godot::classes::object::ConnectFlags;
 use godot::classes::{
-    Button, Engine, IPanel, Label, LineEdit, LinkButton, Os, Panel, ProjectSettings,
+    Button, DisplayServer, Engine, IPanel, Label, LineEdit, LinkButton, Os, Panel, ProjectSettings,
 };
 use godot::global::Error;
 use godot::prelude::*;
 use log::warn;
-use std::sync::{Arc, Mutex};
+use std::cell::RefCell;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use std::{future::Future, rc::Rc};
-use tokio::io::{AsyncBufReadExt, BufReader, stdin};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, stdin};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::watch;
 use tokio::{
     runtime::{self, Runtime},
-    task::JoinHandle,
+    task::{AbortHandle, JoinHandle},
 };
 use veilnet::datagram::Dialer;
 use veilnet::{Connection, DHTAddr};
 use veilnet::{connection::Veilid, datagram::socket::Socket};
 
 const DEFAULT_PORT: i32 = 8910;
+const COPIED_FEEDBACK_SECONDS: f64 = 1.5;
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+const PUBLIC_IP_HOST: &str = "icanhazip.com";
+const PUBLIC_IP_URL: &str = "https://icanhazip.com/";
+const PUBLIC_IP_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the socket task reports its `SocketStats` to the panel
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+/// How often a joined client pings the host to measure round-trip time
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+/// Subkeys tried past the requested one when binding fails, e.g. while a previous session
+/// hasn't released its subkey yet
+const MAX_SUBKEY_FALLBACKS: u16 = 3;
 
 // adapted from MIT licensed https://github.com/2-3-5-41/godot_tokio/tree/master
 #[derive(GodotClass)]
 #[class(base=Object)]
 pub struct TokioRuntime {
     base: Base<Object>,
-    runtime: Rc<Runtime>,
+    /// Taken by `shutdown`, after which the runtime can't be used
+    runtime: Option<Rc<Runtime>>,
+    /// Tasks started through `spawn`, aborted on shutdown
+    tasks: RefCell<Vec<AbortHandle>>,
 }
 
 #[godot_api]
 impl IObject for TokioRuntime {
     fn init(base: Base<Object>) -> Self {
-        let runtime = runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .unwrap();
+        let worker_threads = ProjectSettings::singleton()
+            .get_setting(Self::WORKER_THREADS_SETTING)
+            .try_to::<i64>()
+            .unwrap_or(0);
+        let mut builder = runtime::Builder::new_multi_thread();
+        builder.enable_all();
+        // Unset or zero keeps tokio's default of one worker per core
+        if worker_threads > 0 {
+            builder.worker_threads(worker_threads as usize);
+        }
+        let runtime = builder.build().unwrap();
 
         Self {
             base,
-            runtime: Rc::new(runtime),
+            runtime: Some(Rc::new(runtime)),
+            tasks: RefCell::new(Vec::new()),
         }
     }
 }
@@ -46,52 +82,86 @@ impl IObject for TokioRuntime {
 #[godot_api]
 impl TokioRuntime {
     pub const SINGLETON: &'static str = "TokioRuntime";
+    pub const WORKER_THREADS_SETTING: &'static str = "tbol/network/tokio_worker_threads";
 
+    /// `None` while the singleton isn't registered, e.g. briefly during an editor reload
     fn singleton() -> Option<Gd<TokioRuntime>> {
-        match Engine::singleton().get_singleton(Self::SINGLETON) {
-            Some(singleton) => Some(singleton.cast::<Self>()),
-            None => {
-                panic!("Failed to get singleton");
-            }
-        }
+        Engine::singleton()
+            .get_singleton(Self::SINGLETON)
+            .and_then(|singleton| singleton.try_cast::<Self>().ok())
     }
 
-    pub fn runtime() -> Rc<Runtime> {
-        match Self::singleton() {
-            Some(singleton) => {
-                let bind = singleton.bind();
-                Rc::clone(&bind.runtime)
-            }
-            None => {
-                panic!("Failed to get singleton");
-            }
-        }
+    /// The shared runtime, or why it can't be used right now
+    pub fn runtime() -> Result<Rc<Runtime>, String> {
+        let singleton = Self::singleton()
+            .ok_or_else(|| format!("{} singleton is not registered", Self::SINGLETON))?;
+        let bind = singleton.bind();
+        bind.runtime
+            .as_ref()
+            .map(Rc::clone)
+            .ok_or_else(|| format!("{} used after shutdown", Self::SINGLETON))
     }
 
     /// A wrapper function for the [`tokio::spawn`] function.
-    pub fn spawn<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+    pub fn spawn<F>(future: F) -> Result<tokio::task::JoinHandle<F::Output>, String>
     where
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        Self::runtime().spawn(future)
+        let handle = Self::runtime()?.spawn(future);
+        if let Some(singleton) = Self::singleton() {
+            let bind = singleton.bind();
+            let mut tasks = bind.tasks.borrow_mut();
+            tasks.retain(|task| !task.is_finished());
+            tasks.push(handle.abort_handle());
+        }
+        Ok(handle)
+    }
+
+    /// Abort outstanding tasks and stop the runtime, waiting at most
+    /// `SHUTDOWN_TIMEOUT` for worker threads so game exit can't hang on them.
+    pub fn shutdown(&mut self) {
+        for task in self.tasks.borrow_mut().drain(..) {
+            task.abort();
+        }
+        let Some(runtime) = self.runtime.take() else {
+            return;
+        };
+        match Rc::try_unwrap(runtime) {
+            Ok(runtime) => {
+                let started = Instant::now();
+                runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+                if started.elapsed() >= SHUTDOWN_TIMEOUT {
+                    godot_warn!(
+                        "TokioRuntime tasks did not finish within {:?}, shut down anyway",
+                        SHUTDOWN_TIMEOUT
+                    );
+                }
+            }
+            Err(_) => {
+                godot_warn!("TokioRuntime still referenced at shutdown, dropping without timeout");
+            }
+        }
     }
 
     /// A wrapper function for the [`tokio::block_on`] function.
-    pub fn block_on<F>(future: F) -> F::Output
+    pub fn block_on<F>(future: F) -> Result<F::Output, String>
     where
         F: Future,
     {
-        Self::runtime().block_on(future)
+        Ok(Self::runtime()?.block_on(future))
     }
 
     /// A wrapper function for the [`tokio::spawn_blocking`] function.
-    pub fn spawn_blocking<F, R>(&self, func: F) -> JoinHandle<R>
+    pub fn spawn_blocking<F, R>(&self, func: F) -> Result<JoinHandle<R>, String>
     where
         F: FnOnce() -> R + Send + 'static,
         R: Send + 'static,
     {
-        Self::runtime().spawn_blocking(func)
+        self.runtime
+            .as_ref()
+            .map(|runtime| runtime.spawn_blocking(func))
+            .ok_or_else(|| format!("{} used after shutdown", Self::SINGLETON))
     }
 }
 
@@ -112,29 +182,120 @@ pub struct IslandMultiplayerWizard {
     find_public_ip_button: OnEditor<Gd<LinkButton>>,
     #[export]
     dht_address: OnEditor<Gd<Label>>,
+    #[export]
+    address_edit: OnEditor<Gd<LineEdit>>,
+    #[export]
+    copy_address_button: OnEditor<Gd<Button>>,
+    /// Shows datagram and byte counts for the current session
+    #[export]
+    stats_label: OnEditor<Gd<Label>>,
+    /// Shows the averaged round-trip time to the host while joined
+    #[export]
+    latency_label: OnEditor<Gd<Label>>,
+    /// Socket subkey to bind; give each session on one machine its own
+    #[export]
+    #[init(val = DEFAULT_PORT)]
+    port: i32,
+    /// Seconds left showing "Copied!" and the status text to put back afterwards
+    copied_feedback: Option<(f64, GString)>,
     peer: Option<String>,
+    state: ConnectionState,
     base: Base<Panel>,
     socket_handle: Option<JoinHandle<()>>,
-    tx: Option<Sender<IslandMultiplayerEvent>>,
-    rx: Option<Receiver<IslandMultiplayerEvent>>,
+    /// Tells the socket task to drop its socket and report "disconnected"
+    shutdown_tx: Option<watch::Sender<bool>>,
+    tx: Option<Sender<TaggedEvent>>,
+    rx: Option<Receiver<TaggedEvent>>,
+    /// Bumped for every host or join; events tagged with an older session are dropped
+    session: u64,
+    /// Feeds newly published entries to the host's socket task
+    log_tx: Option<Sender<IslandReplicationLogEntry>>,
+    next_log_entry: u64,
+}
+
+/// Lifecycle of the wizard's connection, emitted to GDScript as an int via `state_changed`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    #[default]
+    Idle = 0,
+    Attaching = 1,
+    Bound = 2,
+    Connecting = 3,
+    Connected = 4,
+    Failed = 5,
 }
 
 pub enum IslandMultiplayerEvent {
     Message(String),
+    StateChanged(ConnectionState),
+    /// The host's bound DHT address, shown so joining players can copy it
+    HostAddress(String),
     Error(String),
+    /// First datagram exchanged with a peer, as opposed to merely having bound the socket:
+    /// the host's reply on clients, each newly joined peer on the host
+    Connected {
+        peer: String,
+    },
     LogEntry(IslandReplicationLogEntry),
+    PublicIp(IpAddr),
+    /// The lookup failed; the wizard falls back to opening the lookup page in a browser
+    PublicIpFailed(String),
+    /// Running totals for the current session, sent every `STATS_INTERVAL`
+    Stats {
+        sent: u64,
+        recv: u64,
+        bytes_in: u64,
+        bytes_out: u64,
+    },
+    /// Round-trip time to the host, averaged over the last few pings
+    Latency(Duration),
+}
+
+/// An event with the session that sent it
+struct TaggedEvent {
+    /// `None` for events outliving any one session, like the public IP lookup
+    session: Option<u64>,
+    event: IslandMultiplayerEvent,
+}
+
+impl TaggedEvent {
+    /// Whether a session other than `current` sent this, after being replaced by it
+    fn is_stale(&self, current: u64) -> bool {
+        self.session.is_some_and(|session| session != current)
+    }
+}
+
+/// Tags everything it sends with one session, so the wizard can tell when a session it has
+/// since replaced is still reporting, e.g. its final "disconnected"
+#[derive(Clone)]
+struct EventSender {
+    session: Option<u64>,
+    tx: Sender<TaggedEvent>,
+}
+
+impl EventSender {
+    async fn send(&self, event: IslandMultiplayerEvent) -> Result<(), SendError<TaggedEvent>> {
+        let session = self.session;
+        self.tx.send(TaggedEvent { session, event }).await
+    }
 }
 
-pub struct IslandReplicationLogEntry {
-    entry: u64,
-    value: Vec<u8>,
+impl From<SocketStatsSnapshot> for IslandMultiplayerEvent {
+    fn from(snapshot: SocketStatsSnapshot) -> Self {
+        IslandMultiplayerEvent::Stats {
+            sent: snapshot.sent,
+            recv: snapshot.recv,
+            bytes_in: snapshot.bytes_in,
+            bytes_out: snapshot.bytes_out,
+        }
+    }
 }
 
 #[godot_api]
 impl IPanel for IslandMultiplayerWizard {
     fn ready(&mut self) {
         self.base_mut().set_process(true);
-        let (tx, rx) = tokio::sync::mpsc::channel::<IslandMultiplayerEvent>(10_000);
+        let (tx, rx) = tokio::sync::mpsc::channel::<TaggedEvent>(10_000);
         self.tx = Some(tx);
         self.rx = Some(rx);
         /*
@@ -217,25 +378,124 @@ impl IPanel for IslandMultiplayerWizard {
             .connect_other_mut(&gd_ref, |this| {
                 this.on_join_pressed();
             });
+        self.copy_address_button
+            .signals()
+            .pressed()
+            .builder()
+            .connect_other_mut(&gd_ref, |this| {
+                this.on_copy_address_pressed();
+            });
+        self.copy_address_button.set_disabled(true);
+        self.find_public_ip_button
+            .signals()
+            .pressed()
+            .builder()
+            .connect_other_mut(&gd_ref, |this| {
+                this.on_find_public_ip_pressed();
+            });
+
+        // Default handlers that mirror the events onto this panel's labels; deferred so they
+        // run after `process` releases its borrow of self
+        self.signals()
+            .peer_message()
+            .builder()
+            .flags(ConnectFlags::DEFERRED)
+            .connect_other_mut(&gd_ref, |this, message: GString| {
+                this.set_status(&message.to_string(), true);
+            });
+        self.signals()
+            .network_error()
+            .builder()
+            .flags(ConnectFlags::DEFERRED)
+            .connect_other_mut(&gd_ref, |this, error: GString| {
+                this.set_status(&error.to_string(), false);
+            });
     }
 
-    fn process(&mut self, _delta: f64) {
-        let event = self.rx.as_mut().unwrap().try_recv();
-        if let Ok(message) = event {
+    fn exit_tree(&mut self) {
+        self.disconnect();
+    }
+
+    fn process(&mut self, delta: f64) {
+        if let Some((remaining, previous)) = self.copied_feedback.take() {
+            if remaining > delta {
+                self.copied_feedback = Some((remaining - delta, previous));
+            } else if self.status_ok.get_text().to_string() == "Copied!" {
+                self.status_ok.set_text(&previous);
+            }
+        }
+
+        let rx = self.rx.as_mut().unwrap();
+        let event = loop {
+            match rx.try_recv() {
+                Ok(tagged) if tagged.is_stale(self.session) => {}
+                received => break received,
+            }
+        };
+        if let Ok(TaggedEvent { event: message, .. }) = event {
             match message {
                 IslandMultiplayerEvent::Message(msg) => {
                     warn!("Received message: {}", msg);
-                    self.dht_address.set_text(msg.as_str());
-                    self.set_status(&msg, true);
+                    self.signals()
+                        .peer_message()
+                        .emit(&GString::from(msg.as_str()));
+                }
+                IslandMultiplayerEvent::StateChanged(state) => {
+                    self.set_state(state);
+                }
+                IslandMultiplayerEvent::HostAddress(addr) => {
+                    warn!("Hosting at: {}", addr);
+                    self.dht_address.set_text(addr.as_str());
+                    self.copy_address_button.set_disabled(false);
+                    self.set_status("Hosting, share the DHT address below.", true);
                 }
                 IslandMultiplayerEvent::Error(err) => {
                     warn!("Received error: {}", err);
-                    self.set_status(&err, false);
+                    self.signals()
+                        .network_error()
+                        .emit(&GString::from(err.as_str()));
+                    self.set_state(ConnectionState::Failed);
                     self.host_button.set_disabled(false);
                     self.join_button.set_disabled(false);
                 }
-                IslandMultiplayerEvent::LogEntry(_entry) => {
-                    warn!("Received log entry");
+                IslandMultiplayerEvent::Connected { peer } => {
+                    warn!("Connected to {}", peer);
+                    self.set_state(ConnectionState::Connected);
+                    self.set_status(&format!("Connected to {}", peer), true);
+                    self.signals()
+                        .peer_connected()
+                        .emit(&GString::from(peer.as_str()));
+                }
+                IslandMultiplayerEvent::LogEntry(entry) => {
+                    self.signals().log_entry_received().emit(
+                        entry.entry as i64,
+                        &PackedByteArray::from(entry.value.as_slice()),
+                    );
+                }
+                IslandMultiplayerEvent::PublicIp(ip) => {
+                    self.port_forward_label
+                        .set_text(&format!("Your public IP address is {}", ip));
+                    self.port_forward_label.show();
+                }
+                IslandMultiplayerEvent::PublicIpFailed(err) => {
+                    warn!("Public IP lookup failed: {}", err);
+                    self.port_forward_label.set_text("");
+                    Os::singleton().shell_open(PUBLIC_IP_URL);
+                }
+                IslandMultiplayerEvent::Stats {
+                    sent,
+                    recv,
+                    bytes_in,
+                    bytes_out,
+                } => {
+                    self.stats_label.set_text(&format!(
+                        "Sent {} datagrams ({} B), received {} ({} B)",
+                        sent, bytes_out, recv, bytes_in
+                    ));
+                }
+                IslandMultiplayerEvent::Latency(rtt) => {
+                    self.latency_label
+                        .set_text(&format!("Ping {} ms", rtt.as_millis()));
                 }
             }
         }
@@ -244,6 +504,68 @@ impl IPanel for IslandMultiplayerWizard {
 
 #[godot_api]
 impl IslandMultiplayerWizard {
+    #[signal]
+    fn state_changed(state: i64);
+
+    /// Status and chat text from the socket task, e.g. "Socket bound on subkey 8910"
+    #[signal]
+    fn peer_message(message: GString);
+
+    /// Emitted once a peer has actually answered; gameplay UI should wait for this rather than
+    /// `state_changed`, which also reports merely binding the socket
+    #[signal]
+    fn peer_connected(peer: GString);
+
+    /// Emitted when the session fails; the connection state moves to `Failed` alongside it
+    #[signal]
+    fn network_error(error: GString);
+
+    /// Emitted on clients for each replicated entry, strictly in log order
+    #[signal]
+    fn log_entry_received(entry: i64, value: PackedByteArray);
+
+    /// Append an entry to the host's replication log and broadcast it to every joined peer
+    #[func]
+    fn publish_log_entry(&mut self, value: PackedByteArray) {
+        let Some(log_tx) = self.log_tx.as_ref() else {
+            warn!("publish_log_entry called without hosting");
+            return;
+        };
+        // Checked before taking an entry number, so clients never wait on a gap
+        if value.len() > MAX_LOG_ENTRY_LEN {
+            godot_error!(
+                "Log entry of {} bytes is over the {} byte limit, not published",
+                value.len(),
+                MAX_LOG_ENTRY_LEN
+            );
+            return;
+        }
+        let entry = IslandReplicationLogEntry {
+            entry: self.next_log_entry,
+            value: value.to_vec(),
+        };
+        if log_tx.try_send(entry).is_err() {
+            warn!(
+                "Replication log queue full or closed, entry {} dropped",
+                self.next_log_entry
+            );
+            return;
+        }
+        self.next_log_entry += 1;
+    }
+
+    #[func]
+    fn get_connection_state(&self) -> i64 {
+        self.state as i64
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        if self.state != state {
+            self.state = state;
+            self.signals().state_changed().emit(state as i64);
+        }
+    }
+
     fn set_status(&mut self, text: &str, is_ok: bool) {
         // Simple way to show status.
         if is_ok {
@@ -255,7 +577,41 @@ impl IslandMultiplayerWizard {
         }
     }
 
+    /// Stop the running socket task, if any, so its recv loop doesn't outlive the session.
+    fn disconnect(&mut self) {
+        let handle = self.socket_handle.take();
+        match self.shutdown_tx.take() {
+            // The task stops at its next await and sends a final "disconnected"
+            Some(shutdown_tx) if shutdown_tx.send(true).is_ok() => {}
+            _ => {
+                if let Some(handle) = handle {
+                    handle.abort();
+                }
+            }
+        }
+        self.log_tx = None;
+    }
+
+    /// A sender for the session about to start; anything still queued from earlier sessions
+    /// is dropped from now on
+    fn new_session_sender(&mut self) -> EventSender {
+        self.session += 1;
+        EventSender {
+            session: Some(self.session),
+            tx: self.tx.clone().unwrap(),
+        }
+    }
+
+    /// A channel whose sender is kept in `shutdown_tx` and whose receiver goes to the new task
+    fn new_shutdown_channel(&mut self) -> watch::Receiver<bool> {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        self.shutdown_tx = Some(shutdown_tx);
+        shutdown_rx
+    }
+
     fn end_game(&mut self, with_error: &str) {
+        self.disconnect();
+
         if self.base().has_node("/root/Pong") {
             // Erase immediately, otherwise network might show
             // errors (this is why we connected deferred above).
@@ -267,64 +623,46 @@ impl IslandMultiplayerWizard {
         self.join_button.set_disabled(false);
 
         self.set_status(with_error, false);
+        self.set_state(ConnectionState::Idle);
     }
 
-    fn on_host_pressed(&mut self) {
-        let tx = self.tx.clone().unwrap();
-        let socket_handle = TokioRuntime::spawn(async move {
-            let mut conn = match Veilid::new().await {
-                Ok(c) => c,
-                Err(e) => {
-                    let _ = tx
-                        .send(IslandMultiplayerEvent::Error(format!(
-                            "Veilid init failed: {}",
-                            e
-                        )))
-                        .await;
-                    return;
-                }
-            };
-            if let Err(e) = conn.require_attachment().await {
-                let _ = tx
-                    .send(IslandMultiplayerEvent::Error(format!(
-                        "Veilid attachment failed: {}",
-                        e
-                    )))
-                    .await;
-                return;
+    /// The exported port as a socket subkey, or `None` after reporting why it is unusable
+    fn validated_port(&mut self) -> Option<u16> {
+        match u16::try_from(self.port) {
+            Ok(port) => Some(port),
+            Err(_) => {
+                let message = format!("Invalid port {}: expected 0-{}", self.port, u16::MAX);
+                self.set_status(&message, false);
+                None
             }
-            let mut sock = match Socket::new(conn, None, 0).await {
-                Ok(s) => s,
-                Err(e) => {
-                    let _ = tx
-                        .send(IslandMultiplayerEvent::Error(format!(
-                            "Socket bind failed: {}",
-                            e
-                        )))
-                        .await;
-                    return;
-                }
-            };
-
-            let message = format!("{}", sock.addr());
-            let _ = tx.send(IslandMultiplayerEvent::Message(message)).await;
+        }
+    }
 
-            loop {
-                match sock.recv_from().await {
-                    Ok((addr, dgram)) => {
-                        warn!(
-                            "{} {}",
-                            addr,
-                            str::from_utf8(dgram.as_slice()).unwrap_or("???")
-                        );
-                    }
-                    Err(err) => {
-                        warn!("error {}", err);
-                    }
-                }
+    fn on_host_pressed(&mut self) {
+        let Some(port) = self.validated_port() else {
+            return;
+        };
+        self.disconnect();
+        self.set_state(ConnectionState::Attaching);
+        let tx = self.new_session_sender();
+        let (log_tx, log_rx) = tokio::sync::mpsc::channel::<IslandReplicationLogEntry>(10_000);
+        self.log_tx = Some(log_tx);
+        self.next_log_entry = 0;
+        self.stats_label.set_text("");
+        self.latency_label.set_text("");
+        let shutdown_rx = self.new_shutdown_channel();
+        let session_tx = tx.clone();
+        // The role is built inside the task because its send interval needs the runtime
+        let session =
+            async move { run_with_reconnect(HostRole::new(log_rx), port, session_tx).await };
+        match TokioRuntime::spawn(until_shutdown(session, shutdown_rx, tx)) {
+            Ok(socket_handle) => self.socket_handle = Some(socket_handle),
+            Err(err) => {
+                godot_error!("Could not start hosting: {}", err);
+                self.end_game(&format!("Networking unavailable: {}", err));
+                return;
             }
-        });
-        self.socket_handle = Some(socket_handle);
+        }
 
         self.host_button.set_disabled(true);
         self.join_button.set_disabled(true);
@@ -332,89 +670,647 @@ impl IslandMultiplayerWizard {
     }
 
     fn on_join_pressed(&mut self) {
-        let addr_str = "127.0.0.1".to_string();
-        let addr: DHTAddr = match addr_str.parse() {
+        let addr_str = self.address_edit.get_text().to_string();
+        let addr: DHTAddr = match addr_str.trim().parse() {
             Ok(a) => a,
             Err(_) => {
                 self.set_status("Invalid DHT address.", false);
                 return;
             }
         };
+        let Some(port) = self.validated_port() else {
+            return;
+        };
 
-        let tx = self.tx.clone().unwrap();
-        let socket_handle = TokioRuntime::spawn(async move {
-            let mut conn = match Veilid::new().await {
-                Ok(c) => c,
-                Err(e) => {
-                    let _ = tx
-                        .send(IslandMultiplayerEvent::Error(format!(
-                            "Veilid init failed: {}",
-                            e
-                        )))
-                        .await;
-                    return;
-                }
+        self.disconnect();
+        self.set_state(ConnectionState::Attaching);
+        let tx = self.new_session_sender();
+        self.stats_label.set_text("");
+        self.latency_label.set_text("");
+        let shutdown_rx = self.new_shutdown_channel();
+        let session_tx = tx.clone();
+        // The role is built inside the task because its ping interval needs the runtime
+        let session =
+            async move { run_with_reconnect(JoinRole::new(addr), port, session_tx).await };
+        match TokioRuntime::spawn(until_shutdown(session, shutdown_rx, tx)) {
+            Ok(socket_handle) => self.socket_handle = Some(socket_handle),
+            Err(err) => {
+                godot_error!("Could not start joining: {}", err);
+                self.end_game(&format!("Networking unavailable: {}", err));
+                return;
+            }
+        }
+
+        self.host_button.set_disabled(true);
+        self.join_button.set_disabled(true);
+        self.set_status("Connecting...", true);
+    }
+
+    fn on_copy_address_pressed(&mut self) {
+        let address = self.dht_address.get_text();
+        if address.is_empty() {
+            return;
+        }
+        DisplayServer::singleton().clipboard_set(&address);
+        let previous = self.status_ok.get_text();
+        self.set_status("Copied!", true);
+        self.copied_feedback = Some((COPIED_FEEDBACK_SECONDS, previous));
+    }
+
+    fn on_find_public_ip_pressed(&mut self) {
+        self.port_forward_label
+            .set_text("Looking up your public IP address...");
+        let tx = EventSender {
+            session: None,
+            tx: self.tx.clone().unwrap(),
+        };
+        let lookup = TokioRuntime::spawn(async move {
+            let event = match tokio::time::timeout(PUBLIC_IP_TIMEOUT, fetch_public_ip()).await {
+                Ok(Ok(ip)) => IslandMultiplayerEvent::PublicIp(ip),
+                Ok(Err(err)) => IslandMultiplayerEvent::PublicIpFailed(err),
+                Err(_) => IslandMultiplayerEvent::PublicIpFailed(format!(
+                    "no response within {:?}",
+                    PUBLIC_IP_TIMEOUT
+                )),
             };
-            if let Err(e) = conn.require_attachment().await {
-                let _ = tx
+            let _ = tx.send(event).await;
+        });
+        if let Err(err) = lookup {
+            // Same fallback as a failed lookup: let the browser show it
+            godot_error!("Could not look up the public IP address: {}", err);
+            self.port_forward_label.set_text("");
+            Os::singleton().shell_open(PUBLIC_IP_URL);
+        }
+    }
+}
+
+/// Ask icanhazip.com for our address over plain HTTP/1.0, so the body isn't chunked
+async fn fetch_public_ip() -> Result<IpAddr, String> {
+    let mut stream = TcpStream::connect((PUBLIC_IP_HOST, 80))
+        .await
+        .map_err(|e| format!("connecting to {} failed: {}", PUBLIC_IP_HOST, e))?;
+    let request = format!("GET / HTTP/1.0\r\nHost: {}\r\n\r\n", PUBLIC_IP_HOST);
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("sending request failed: {}", e))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("reading response failed: {}", e))?;
+    parse_public_ip_response(&response)
+}
+
+/// Bind a socket on `subkey`, moving on to the next few subkeys while binding fails.
+/// Returns the socket with the subkey it got, or the error from the last attempt.
+async fn bind_free_subkey(conn: Veilid, subkey: u16) -> Result<(Socket, u16), String> {
+    let mut candidate = subkey;
+    loop {
+        match Socket::new(conn.clone(), None, candidate).await {
+            Ok(sock) => return Ok((sock, candidate)),
+            Err(e) => {
+                let next = candidate
+                    .checked_add(1)
+                    .filter(|next| next - subkey <= MAX_SUBKEY_FALLBACKS);
+                let Some(next) = next else {
+                    return Err(e.to_string());
+                };
+                warn!("Subkey {} unavailable, trying {}: {}", candidate, next, e);
+                candidate = next;
+            }
+        }
+    }
+}
+
+/// The channel back to the wizard and the socket counters, shared by a session and its role
+struct SessionIo {
+    tx: EventSender,
+    stats: SocketStats,
+}
+
+/// What a host or join session does on top of the socket handling in `run_with_reconnect`
+trait SessionRole: Send {
+    /// Produced by `next_event` and handled by `on_event` between datagrams
+    type Event: Send;
+
+    /// Set up a freshly bound socket; runs again after every reconnect.
+    /// An error is handled like a failed bind.
+    fn on_bound(
+        &mut self,
+        sock: &mut Socket,
+        io: &SessionIo,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Handle a decoded datagram; chat is reported by `run_with_reconnect` itself
+    fn on_message(
+        &mut self,
+        sock: &mut Socket,
+        addr: DHTAddr,
+        message: NetMessage,
+        io: &SessionIo,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// Wait for the role's next event; `None` skips it until the loop comes around again
+    fn next_event(&mut self) -> impl Future<Output = Option<Self::Event>> + Send;
+
+    /// Whether datagrams from `addr` skip the receive rate limiter
+    fn is_rate_limit_exempt(&self, _addr: &DHTAddr) -> bool {
+        false
+    }
+
+    fn on_event(
+        &mut self,
+        sock: &mut Socket,
+        event: Self::Event,
+        io: &SessionIo,
+    ) -> impl Future<Output = ()> + Send;
+}
+
+/// Bind a socket and run `role` on it, rebinding with backoff after repeated receive errors
+/// until `MAX_RECONNECT_ATTEMPTS` in a row have failed. If the first bind fails, that is
+/// reported as an error straight away.
+async fn run_with_reconnect<R: SessionRole>(mut role: R, port: u16, tx: EventSender) {
+    let io = SessionIo {
+        tx,
+        stats: SocketStats::default(),
+    };
+    let mut retries: u32 = 0;
+    let mut stats_interval = tokio::time::interval(STATS_INTERVAL);
+    'session: loop {
+        if retries > 0 {
+            if retries > MAX_RECONNECT_ATTEMPTS {
+                let _ = io
+                    .tx
                     .send(IslandMultiplayerEvent::Error(format!(
-                        "Veilid attachment failed: {}",
-                        e
+                        "Connection lost, gave up after {} reconnect attempts",
+                        MAX_RECONNECT_ATTEMPTS
                     )))
                     .await;
                 return;
             }
-            // Use port 0 (or any valid subkey) for the client's ephemeral socket
-            let mut sock = match Socket::new(conn, None, 0).await {
-                Ok(s) => s,
-                Err(e) => {
-                    let _ = tx
-                        .send(IslandMultiplayerEvent::Error(format!(
-                            "Socket init failed: {}",
-                            e
-                        )))
-                        .await;
-                    return;
-                }
-            };
-
-            let _ = tx
-                .send(IslandMultiplayerEvent::Message(
-                    "Connected (sending ping...)".to_string(),
+            let delay = reconnect_delay(retries);
+            let _ = io
+                .tx
+                .send(IslandMultiplayerEvent::Message(format!(
+                    "Connection lost, reconnecting in {} ms (attempt {} of {})",
+                    delay.as_millis(),
+                    retries,
+                    MAX_RECONNECT_ATTEMPTS
+                )))
+                .await;
+            let _ = io
+                .tx
+                .send(IslandMultiplayerEvent::StateChanged(
+                    ConnectionState::Attaching,
                 ))
                 .await;
+            tokio::time::sleep(delay).await;
+        }
 
-            if let Err(e) = sock.send_to(&addr, b"ping").await {
-                let _ = tx
-                    .send(IslandMultiplayerEvent::Error(format!("Send failed: {}", e)))
-                    .await;
+        let mut sock = match bind_session(&mut role, port, &io).await {
+            Ok(sock) => sock,
+            Err(e) if retries > 0 => {
+                warn!("{}", e);
+                retries += 1;
+                continue 'session;
+            }
+            Err(e) => {
+                let _ = io.tx.send(IslandMultiplayerEvent::Error(e)).await;
                 return;
             }
+        };
 
-            loop {
-                match sock.recv_from().await {
+        let mut recv_errors: u32 = 0;
+        let mut limiter = SenderRateLimiter::new(RECV_RATE_PER_SEC, RECV_BURST, Instant::now());
+        loop {
+            tokio::select! {
+                received = sock.recv_from() => match received {
                     Ok((addr, dgram)) => {
-                        warn!(
-                            "{} {}",
-                            addr,
-                            str::from_utf8(dgram.as_slice()).unwrap_or("???")
-                        );
+                        recv_errors = 0;
+                        retries = 0;
+                        io.stats.record_recv(dgram.len());
+                        if !role.is_rate_limit_exempt(&addr)
+                            && !admit_datagram(&mut limiter, &addr, &io.tx).await
+                        {
+                            continue;
+                        }
+                        match NetMessage::decode(&dgram) {
+                            Ok(NetMessage::Chat(text)) => {
+                                let _ = io
+                                    .tx
+                                    .send(IslandMultiplayerEvent::Message(format!(
+                                        "{}: {}",
+                                        addr, text
+                                    )))
+                                    .await;
+                            }
+                            Ok(message) => role.on_message(&mut sock, addr, message, &io).await,
+                            Err(err) => {
+                                warn!("dropping datagram from {}: {}", addr, err);
+                            }
+                        }
                     }
                     Err(err) => {
                         warn!("error {}", err);
+                        recv_errors += 1;
+                        if recv_errors >= RECV_ERRORS_BEFORE_RECONNECT {
+                            retries += 1;
+                            continue 'session;
+                        }
                     }
+                },
+                Some(event) = role.next_event() => role.on_event(&mut sock, event, &io).await,
+                _ = stats_interval.tick() => {
+                    let _ = io.tx.send(io.stats.snapshot().into()).await;
                 }
             }
-        });
-        self.socket_handle = Some(socket_handle);
+        }
+    }
+}
 
-        self.host_button.set_disabled(true);
-        self.join_button.set_disabled(true);
-        self.set_status("Connecting...", true);
+/// Attach to Veilid, bind a socket at or just past `port` and hand it to `role` to set up
+async fn bind_session<R: SessionRole>(
+    role: &mut R,
+    port: u16,
+    io: &SessionIo,
+) -> Result<Socket, String> {
+    let mut conn = Veilid::new()
+        .await
+        .map_err(|e| format!("Veilid init failed: {}", e))?;
+    conn.require_attachment()
+        .await
+        .map_err(|e| format!("Veilid attachment failed: {}", e))?;
+    let (mut sock, subkey) = bind_free_subkey(conn, port)
+        .await
+        .map_err(|e| format!("Socket bind failed: {}", e))?;
+    let _ = io
+        .tx
+        .send(IslandMultiplayerEvent::StateChanged(ConnectionState::Bound))
+        .await;
+    let _ = io
+        .tx
+        .send(IslandMultiplayerEvent::Message(format!(
+            "Socket bound on subkey {}",
+            subkey
+        )))
+        .await;
+    role.on_bound(&mut sock, io).await?;
+    Ok(sock)
+}
+
+/// What wakes the host between datagrams
+enum HostEvent {
+    /// The wizard published a new log entry
+    Publish(IslandReplicationLogEntry),
+    /// Time to send the next batch from the outbox
+    Flush,
+}
+
+/// Keeps the replication log, broadcasting new entries and replaying it to joining peers
+struct HostRole {
+    log_rx: Receiver<IslandReplicationLogEntry>,
+    /// Every entry published so far, replayed in full to each newly joined peer
+    log: Vec<IslandReplicationLogEntry>,
+    peers: Vec<DHTAddr>,
+    /// Log datagrams not yet sent, keyed by the peer's address string
+    outbox: LogOutbox<String>,
+    send_interval: tokio::time::Interval,
+}
+
+impl HostRole {
+    /// Must be called on the runtime, which the send interval needs
+    fn new(log_rx: Receiver<IslandReplicationLogEntry>) -> Self {
+        let mut send_interval = tokio::time::interval(LOG_SEND_INTERVAL);
+        // An idle host shouldn't catch up on missed ticks by sending batches back to back
+        send_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        HostRole {
+            log_rx,
+            log: Vec::new(),
+            peers: Vec::new(),
+            outbox: LogOutbox::default(),
+            send_interval,
+        }
+    }
+
+    fn peer(&self, addr: &str) -> Option<&DHTAddr> {
+        self.peers.iter().find(|peer| peer.to_string() == addr)
+    }
+
+    fn is_peer(&self, addr: &DHTAddr) -> bool {
+        self.peer(&addr.to_string()).is_some()
+    }
+
+    /// Tell `addr` how far the log goes, once nothing is still queued for them
+    async fn send_log_head(&self, sock: &mut Socket, addr: &DHTAddr, io: &SessionIo) {
+        let to = addr.to_string();
+        if self.outbox.is_sending_to(|queued_for| *queued_for == to) {
+            return;
+        }
+        let next = self.log.last().map_or(0, |entry| entry.entry + 1);
+        let head = NetMessage::LogHead(next).encode();
+        if let Err(err) = sock
+            .send_to(addr, &head)
+            .await
+            .inspect(|_| io.stats.record_sent(head.len()))
+        {
+            warn!("log head to {} failed: {}", addr, err);
+        }
+    }
+
+    /// Queue every logged entry numbered `from` or later for `addr`, replaying or resending the log
+    fn queue_log_from(&mut self, from: u64, addr: &DHTAddr) {
+        let to = addr.to_string();
+        for entry in self.log.iter().filter(|entry| entry.entry >= from) {
+            match log_entry_datagrams(entry) {
+                Ok(datagrams) => self.outbox.push(&to, datagrams),
+                Err(err) => warn!("not sending to {}: {}", addr, err),
+            }
+        }
+    }
+}
+
+impl SessionRole for HostRole {
+    type Event = HostEvent;
+
+    async fn on_bound(&mut self, sock: &mut Socket, io: &SessionIo) -> Result<(), String> {
+        // Peers of the previous socket join again by pinging the new address
+        self.peers.clear();
+        self.outbox = LogOutbox::default();
+        let address = format!("{}", sock.addr());
+        let _ = io
+            .tx
+            .send(IslandMultiplayerEvent::HostAddress(address))
+            .await;
+        Ok(())
+    }
+
+    async fn on_message(
+        &mut self,
+        sock: &mut Socket,
+        addr: DHTAddr,
+        message: NetMessage,
+        io: &SessionIo,
+    ) {
+        match message {
+            NetMessage::Ping(stamp) => {
+                let pong = NetMessage::Pong(stamp).encode();
+                if let Err(err) = sock
+                    .send_to(&addr, &pong)
+                    .await
+                    .inspect(|_| io.stats.record_sent(pong.len()))
+                {
+                    warn!("pong to {} failed: {}", addr, err);
+                }
+                if self.is_peer(&addr) {
+                    self.send_log_head(sock, &addr, io).await;
+                    return;
+                }
+                self.queue_log_from(0, &addr);
+                let peer = addr.to_string();
+                self.peers.push(addr);
+                let _ = io.tx.send(IslandMultiplayerEvent::Connected { peer }).await;
+            }
+            NetMessage::Resend(from) => {
+                if !self.is_peer(&addr) {
+                    return;
+                }
+                // The resend covers whatever was still queued for this peer
+                let to = addr.to_string();
+                self.outbox.cancel(|queued_for| *queued_for == to);
+                self.queue_log_from(from, &addr);
+            }
+            NetMessage::Pong(_)
+            | NetMessage::Log(_)
+            | NetMessage::LogChunk(_)
+            | NetMessage::LogHead(_)
+            | NetMessage::Chat(_) => {}
+        }
+    }
+
+    async fn next_event(&mut self) -> Option<HostEvent> {
+        tokio::select! {
+            Some(entry) = self.log_rx.recv() => Some(HostEvent::Publish(entry)),
+            _ = self.send_interval.tick(), if !self.outbox.is_empty() => Some(HostEvent::Flush),
+            else => None,
+        }
+    }
+
+    async fn on_event(&mut self, sock: &mut Socket, event: HostEvent, io: &SessionIo) {
+        match event {
+            HostEvent::Publish(entry) => {
+                match log_entry_datagrams(&entry) {
+                    Ok(datagrams) => {
+                        for peer in &self.peers {
+                            self.outbox.push(&peer.to_string(), datagrams.clone());
+                        }
+                    }
+                    Err(err) => warn!("not broadcasting: {}", err),
+                }
+                self.log.push(entry);
+            }
+            HostEvent::Flush => {
+                for (to, bytes) in self.outbox.next_batch() {
+                    let Some(peer) = self.peer(&to) else {
+                        continue;
+                    };
+                    if let Err(err) = sock
+                        .send_to(peer, &bytes)
+                        .await
+                        .inspect(|_| io.stats.record_sent(bytes.len()))
+                    {
+                        warn!("sending log to {} failed: {}", peer, err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pings the host to keep the session alive and applies its log entries in order
+struct JoinRole {
+    host: DHTAddr,
+    /// Whether the host has answered since the socket was last bound
+    connected: bool,
+    /// Ping timestamps are microseconds since this instant
+    started: Instant,
+    latency: LatencyTracker,
+    ping_interval: tokio::time::Interval,
+    receiver: ReplicationLogReceiver,
+    reassembler: ChunkReassembler,
+}
+
+impl JoinRole {
+    /// Must be called on the runtime, which the ping interval needs
+    fn new(host: DHTAddr) -> Self {
+        JoinRole {
+            host,
+            connected: false,
+            started: Instant::now(),
+            latency: LatencyTracker::default(),
+            ping_interval: tokio::time::interval_at(
+                tokio::time::Instant::now() + PING_INTERVAL,
+                PING_INTERVAL,
+            ),
+            receiver: ReplicationLogReceiver::default(),
+            reassembler: ChunkReassembler::default(),
+        }
     }
 
-    fn _on_find_public_ip_pressed(&mut self) {
-        let mut os = Os::singleton();
-        os.shell_open("https://icanhazip.com/");
+    fn is_host(&self, addr: &DHTAddr) -> bool {
+        addr.to_string() == self.host.to_string()
+    }
+
+    async fn apply(&mut self, entry: IslandReplicationLogEntry, io: &SessionIo) {
+        for ready in self.receiver.receive(entry) {
+            let _ = io.tx.send(IslandMultiplayerEvent::LogEntry(ready)).await;
+        }
+    }
+}
+
+impl SessionRole for JoinRole {
+    type Event = ();
+
+    async fn on_bound(&mut self, sock: &mut Socket, io: &SessionIo) -> Result<(), String> {
+        self.connected = false;
+        let ping = NetMessage::Ping(self.started.elapsed().as_micros() as u64).encode();
+        sock.send_to(&self.host, &ping)
+            .await
+            .map_err(|e| format!("Send failed: {}", e))?;
+        io.stats.record_sent(ping.len());
+        let _ = io
+            .tx
+            .send(IslandMultiplayerEvent::StateChanged(
+                ConnectionState::Connecting,
+            ))
+            .await;
+        Ok(())
+    }
+
+    async fn on_message(
+        &mut self,
+        sock: &mut Socket,
+        addr: DHTAddr,
+        message: NetMessage,
+        io: &SessionIo,
+    ) {
+        match message {
+            NetMessage::Pong(stamp) if self.is_host(&addr) => {
+                if !self.connected {
+                    self.connected = true;
+                    let _ = io
+                        .tx
+                        .send(IslandMultiplayerEvent::Connected {
+                            peer: self.host.to_string(),
+                        })
+                        .await;
+                }
+                let now = self.started.elapsed().as_micros() as u64;
+                let rtt = Duration::from_micros(now.saturating_sub(stamp));
+                let average = self.latency.record(rtt);
+                let _ = io.tx.send(IslandMultiplayerEvent::Latency(average)).await;
+            }
+            NetMessage::Ping(stamp) => {
+                let pong = NetMessage::Pong(stamp).encode();
+                if let Err(err) = sock
+                    .send_to(&addr, &pong)
+                    .await
+                    .inspect(|_| io.stats.record_sent(pong.len()))
+                {
+                    warn!("pong to {} failed: {}", addr, err);
+                }
+            }
+            NetMessage::Log(entry) if self.is_host(&addr) => self.apply(entry, io).await,
+            NetMessage::LogChunk(chunk) if self.is_host(&addr) => {
+                if let Some(entry) = self.reassembler.accept(chunk, Instant::now()) {
+                    self.apply(entry, io).await;
+                }
+            }
+            NetMessage::LogHead(next) if self.is_host(&addr) => self.receiver.note_host_next(next),
+            // Only the host may feed the log, and no one asks a client
+            NetMessage::Pong(_)
+            | NetMessage::Log(_)
+            | NetMessage::LogChunk(_)
+            | NetMessage::LogHead(_)
+            | NetMessage::Resend(_)
+            | NetMessage::Chat(_) => {}
+        }
+    }
+
+    async fn next_event(&mut self) -> Option<()> {
+        self.ping_interval.tick().await;
+        Some(())
+    }
+
+    /// The host paces its log to stay under the limit, and throttling it would only cost resends
+    fn is_rate_limit_exempt(&self, addr: &DHTAddr) -> bool {
+        self.is_host(addr)
+    }
+
+    async fn on_event(&mut self, sock: &mut Socket, _: (), io: &SessionIo) {
+        if let Some(from) = self.receiver.resend_from(Instant::now()) {
+            let resend = NetMessage::Resend(from).encode();
+            if let Err(err) = sock
+                .send_to(&self.host, &resend)
+                .await
+                .inspect(|_| io.stats.record_sent(resend.len()))
+            {
+                warn!("asking {} to resend the log failed: {}", self.host, err);
+            }
+        }
+        let stamp = self.started.elapsed().as_micros() as u64;
+        let ping = NetMessage::Ping(stamp).encode();
+        if let Err(err) = sock
+            .send_to(&self.host, &ping)
+            .await
+            .inspect(|_| io.stats.record_sent(ping.len()))
+        {
+            warn!("ping to {} failed: {}", self.host, err);
+        }
+    }
+}
+
+/// Run an inbound datagram from `addr` past its rate limiter, telling the player when
+/// throttling starts and ends. Returns whether the datagram should be handled.
+async fn admit_datagram(limiter: &mut SenderRateLimiter, addr: &DHTAddr, tx: &EventSender) -> bool {
+    match limiter.check(&addr.to_string(), Instant::now()) {
+        Throttle::Pass => true,
+        Throttle::Started => {
+            let _ = tx
+                .send(IslandMultiplayerEvent::Message(format!(
+                    "Receiving more than {} datagrams per second from {}, dropping the excess",
+                    RECV_RATE_PER_SEC, addr
+                )))
+                .await;
+            false
+        }
+        Throttle::Dropped => false,
+        Throttle::Ended { dropped } => {
+            let _ = tx
+                .send(IslandMultiplayerEvent::Message(format!(
+                    "Traffic from {} back under the limit after dropping {} datagrams",
+                    addr, dropped
+                )))
+                .await;
+            true
+        }
+    }
+}
+
+/// Drive a host or join session until it ends on its own or `shutdown` fires. Shutting down
+/// drops the session wherever it is waiting, usually `recv_from`, taking its socket with it.
+async fn until_shutdown(
+    session: impl Future<Output = ()>,
+    mut shutdown: watch::Receiver<bool>,
+    tx: EventSender,
+) {
+    tokio::select! {
+        _ = session => {}
+        // Also fires if the wizard is freed and the sender dropped
+        _ = shutdown.changed() => {
+            let _ = tx
+                .send(IslandMultiplayerEvent::Message("disconnected".to_string()))
+                .await;
+        }
     }
 }
@@ -0,0 +1,1007 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Consecutive `recv_from` failures tolerated before the socket is torn down and rebuilt
+pub const RECV_ERRORS_BEFORE_RECONNECT: u32 = 3;
+/// Reconnects attempted in a row before the session is reported as failed
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 6;
+const RECONNECT_BASE_DELAY_MS: u64 = 100;
+const RECONNECT_MAX_DELAY_MS: u64 = 5_000;
+/// Round trips averaged by `LatencyTracker`
+const LATENCY_SAMPLES: usize = 8;
+/// Inbound datagrams a socket task handles per second once its burst is used up
+pub const RECV_RATE_PER_SEC: u32 = 200;
+/// Inbound datagrams handled back to back before `RECV_RATE_PER_SEC` applies
+pub const RECV_BURST: u32 = 400;
+/// Senders tracked with their own bucket; once full, idle ones are forgotten and any
+/// further newcomers share a single bucket
+const MAX_RATE_LIMITED_SENDERS: usize = 256;
+/// How often the host drains its `LogOutbox`
+pub const LOG_SEND_INTERVAL: Duration = Duration::from_millis(50);
+/// Datagrams sent to all peers together per `LOG_SEND_INTERVAL`, keeping a replay at
+/// `RECV_RATE_PER_SEC` so it never eats into a receiver's burst
+const LOG_DATAGRAMS_PER_SEND: usize = 10;
+
+/// Entries this far or further past the next expected one are dropped by
+/// `ReplicationLogReceiver`, so forged entry numbers can't make it buffer without bound
+pub const LOG_REORDER_WINDOW: u64 = 1024;
+/// How long a gap may hold back buffered entries before the client asks for a resend
+pub const LOG_GAP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Bytes preceding the payload: entry number (u64) then payload length (u32), both big-endian
+const LOG_ENTRY_HEADER_LEN: usize = 12;
+/// Bytes preceding a fragment: entry number (u64), chunk index and chunk count (u16 each)
+const LOG_CHUNK_HEADER_LEN: usize = 12;
+/// Bytes `NetMessage::encode` adds around a body: the u32 length and the tag
+const FRAME_HEADER_LEN: usize = 5;
+/// Largest datagram the socket tasks send; bigger log entries go out as `LogChunk`s
+pub const MAX_DATAGRAM_LEN: usize = 1200;
+/// Payload bytes carried by each `LogChunk`
+const LOG_FRAGMENT_LEN: usize = MAX_DATAGRAM_LEN - FRAME_HEADER_LEN - LOG_CHUNK_HEADER_LEN;
+/// Largest log entry value, sent in at most `RECV_BURST` chunks so a receiver can take it
+/// in one burst
+pub const MAX_LOG_ENTRY_LEN: usize = LOG_FRAGMENT_LEN * RECV_BURST as usize;
+/// A chunked entry still missing pieces this long after its first chunk arrived is dropped
+pub const CHUNK_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One entry in the host's replication log, numbered from 0 with no gaps
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IslandReplicationLogEntry {
+    pub entry: u64,
+    pub value: Vec<u8>,
+}
+
+impl IslandReplicationLogEntry {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(LOG_ENTRY_HEADER_LEN + self.value.len());
+        bytes.extend_from_slice(&self.entry.to_be_bytes());
+        bytes.extend_from_slice(&(self.value.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < LOG_ENTRY_HEADER_LEN {
+            return Err(format!(
+                "Log entry too short: {} bytes, header needs {}",
+                bytes.len(),
+                LOG_ENTRY_HEADER_LEN
+            ));
+        }
+        let entry = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        let len = u32::from_be_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let value = &bytes[LOG_ENTRY_HEADER_LEN..];
+        if value.len() != len {
+            return Err(format!(
+                "Log entry {} declares {} payload bytes but carries {}",
+                entry,
+                len,
+                value.len()
+            ));
+        }
+        Ok(Self {
+            entry,
+            value: value.to_vec(),
+        })
+    }
+}
+
+/// Client-side reorder buffer: holds entries that arrive ahead of a gap
+/// and releases them only once every earlier entry has been applied
+#[derive(Debug, Default)]
+pub struct ReplicationLogReceiver {
+    next_entry: u64,
+    pending: BTreeMap<u64, Vec<u8>>,
+    /// Highest entry count the host has advertised with `LogHead`
+    host_next: u64,
+    /// When `resend_from` first saw the current gap
+    gap_since: Option<Instant>,
+}
+
+impl ReplicationLogReceiver {
+    /// Entry number the receiver is waiting on
+    pub fn next_entry(&self) -> u64 {
+        self.next_entry
+    }
+
+    /// Accept an entry and return every entry now ready to apply, in order.
+    /// Duplicates, entries already applied and entries `LOG_REORDER_WINDOW` or more ahead
+    /// are dropped; the last kind comes back with the resend that closes the gap.
+    pub fn receive(&mut self, entry: IslandReplicationLogEntry) -> Vec<IslandReplicationLogEntry> {
+        if entry.entry < self.next_entry || entry.entry - self.next_entry >= LOG_REORDER_WINDOW {
+            return Vec::new();
+        }
+        self.pending.entry(entry.entry).or_insert(entry.value);
+
+        let mut ready = Vec::new();
+        while let Some(value) = self.pending.remove(&self.next_entry) {
+            ready.push(IslandReplicationLogEntry {
+                entry: self.next_entry,
+                value,
+            });
+            self.next_entry += 1;
+        }
+        if !ready.is_empty() {
+            self.gap_since = None;
+        }
+        ready
+    }
+
+    /// Record the host's `LogHead`: it has logged every entry before `host_next`
+    pub fn note_host_next(&mut self, host_next: u64) {
+        self.host_next = self.host_next.max(host_next);
+    }
+
+    /// Entry to ask the host to resend from once a gap has held back buffered entries, or
+    /// entries the host advertised haven't arrived, for `LOG_GAP_TIMEOUT`.
+    /// Meant to be polled; after asking, it waits another timeout.
+    pub fn resend_from(&mut self, now: Instant) -> Option<u64> {
+        if self.pending.is_empty() && self.host_next <= self.next_entry {
+            self.gap_since = None;
+            return None;
+        }
+        let since = *self.gap_since.get_or_insert(now);
+        if now.duration_since(since) < LOG_GAP_TIMEOUT {
+            return None;
+        }
+        self.gap_since = Some(now);
+        Some(self.next_entry)
+    }
+}
+
+/// One piece of a log entry too big for a single datagram. The entry number doubles as the
+/// message id, since the host numbers every entry uniquely.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogChunk {
+    pub entry: u64,
+    pub index: u16,
+    pub total: u16,
+    pub fragment: Vec<u8>,
+}
+
+impl LogChunk {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(LOG_CHUNK_HEADER_LEN + self.fragment.len());
+        bytes.extend_from_slice(&self.entry.to_be_bytes());
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(&self.total.to_be_bytes());
+        bytes.extend_from_slice(&self.fragment);
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < LOG_CHUNK_HEADER_LEN {
+            return Err(format!(
+                "Log chunk too short: {} bytes, header needs {}",
+                bytes.len(),
+                LOG_CHUNK_HEADER_LEN
+            ));
+        }
+        let entry = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        let index = u16::from_be_bytes(bytes[8..10].try_into().unwrap());
+        let total = u16::from_be_bytes(bytes[10..12].try_into().unwrap());
+        if index >= total {
+            return Err(format!(
+                "Log chunk {} of entry {} is outside its count of {}",
+                index, entry, total
+            ));
+        }
+        Ok(Self {
+            entry,
+            index,
+            total,
+            fragment: bytes[LOG_CHUNK_HEADER_LEN..].to_vec(),
+        })
+    }
+}
+
+/// The datagrams carrying `entry`: a single `Log` message when it fits in `MAX_DATAGRAM_LEN`,
+/// otherwise numbered `LogChunk`s for `ChunkReassembler` to put back together.
+/// Fails for values over `MAX_LOG_ENTRY_LEN`, which receivers would throttle.
+pub fn log_entry_datagrams(entry: &IslandReplicationLogEntry) -> Result<Vec<Vec<u8>>, String> {
+    if FRAME_HEADER_LEN + LOG_ENTRY_HEADER_LEN + entry.value.len() <= MAX_DATAGRAM_LEN {
+        return Ok(vec![NetMessage::Log(entry.clone()).encode()]);
+    }
+    let too_large = || {
+        format!(
+            "Log entry {} is {} bytes, over the {} byte limit",
+            entry.entry,
+            entry.value.len(),
+            MAX_LOG_ENTRY_LEN
+        )
+    };
+    if entry.value.len() > MAX_LOG_ENTRY_LEN {
+        return Err(too_large());
+    }
+    let fragments: Vec<&[u8]> = entry.value.chunks(LOG_FRAGMENT_LEN).collect();
+    let total = u16::try_from(fragments.len()).map_err(|_| too_large())?;
+    let datagrams = (0..total)
+        .zip(fragments)
+        .map(|(index, fragment)| {
+            NetMessage::LogChunk(LogChunk {
+                entry: entry.entry,
+                index,
+                total,
+                fragment: fragment.to_vec(),
+            })
+            .encode()
+        })
+        .collect();
+    Ok(datagrams)
+}
+
+/// Chunks received so far for one entry
+#[derive(Debug)]
+struct PartialEntry {
+    total: u16,
+    fragments: BTreeMap<u16, Vec<u8>>,
+    first_seen: Instant,
+}
+
+/// Client-side counterpart of `log_entry_datagrams`: collects `LogChunk`s, in any order,
+/// until an entry is complete
+#[derive(Debug, Default)]
+pub struct ChunkReassembler {
+    partial: HashMap<u64, PartialEntry>,
+}
+
+impl ChunkReassembler {
+    /// Entries with some but not all of their chunks in
+    pub fn pending(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Add a chunk that arrived at `now` and return its entry once the last piece is in.
+    /// Entries older than `CHUNK_REASSEMBLY_TIMEOUT` are dropped first, so a lost chunk
+    /// can't hold memory forever.
+    pub fn accept(&mut self, chunk: LogChunk, now: Instant) -> Option<IslandReplicationLogEntry> {
+        self.partial
+            .retain(|_, partial| now.duration_since(partial.first_seen) < CHUNK_REASSEMBLY_TIMEOUT);
+
+        let partial = self
+            .partial
+            .entry(chunk.entry)
+            .or_insert_with(|| PartialEntry {
+                total: chunk.total,
+                fragments: BTreeMap::new(),
+                first_seen: now,
+            });
+        // A different count means a stale half from an earlier send; start over
+        if partial.total != chunk.total {
+            *partial = PartialEntry {
+                total: chunk.total,
+                fragments: BTreeMap::new(),
+                first_seen: now,
+            };
+        }
+        partial.fragments.insert(chunk.index, chunk.fragment);
+        if partial.fragments.len() < partial.total as usize {
+            return None;
+        }
+
+        let partial = self.partial.remove(&chunk.entry)?;
+        Some(IslandReplicationLogEntry {
+            entry: chunk.entry,
+            value: partial.fragments.into_values().flatten().collect(),
+        })
+    }
+}
+
+const TAG_PING: u8 = 0;
+const TAG_PONG: u8 = 1;
+const TAG_LOG: u8 = 2;
+const TAG_CHAT: u8 = 3;
+const TAG_LOG_CHUNK: u8 = 4;
+const TAG_RESEND: u8 = 5;
+const TAG_LOG_HEAD: u8 = 6;
+
+/// Everything exchanged over the datagram socket.
+/// Framed as a u32 big-endian length covering the tag and body, a 1-byte tag, then the body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetMessage {
+    /// Carries the sender's clock in microseconds, echoed back unchanged in the `Pong`
+    Ping(u64),
+    Pong(u64),
+    Log(IslandReplicationLogEntry),
+    Chat(String),
+    LogChunk(LogChunk),
+    /// Sent by a client whose log stalled on a gap: replay every entry from this one on
+    Resend(u64),
+    /// Sent by the host with each `Pong` once a client's log is sent: the number its next
+    /// entry will get, so the client notices entries lost off the end of the log
+    LogHead(u64),
+}
+
+impl NetMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let (tag, body) = match self {
+            NetMessage::Ping(stamp) => (TAG_PING, stamp.to_be_bytes().to_vec()),
+            NetMessage::Pong(stamp) => (TAG_PONG, stamp.to_be_bytes().to_vec()),
+            NetMessage::Log(entry) => (TAG_LOG, entry.encode()),
+            NetMessage::Chat(text) => (TAG_CHAT, text.as_bytes().to_vec()),
+            NetMessage::LogChunk(chunk) => (TAG_LOG_CHUNK, chunk.encode()),
+            NetMessage::Resend(entry) => (TAG_RESEND, entry.to_be_bytes().to_vec()),
+            NetMessage::LogHead(entry) => (TAG_LOG_HEAD, entry.to_be_bytes().to_vec()),
+        };
+        let mut bytes = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
+        bytes.extend_from_slice(&(1 + body.len() as u32).to_be_bytes());
+        bytes.push(tag);
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 5 {
+            return Err(format!("Message too short: {} bytes", bytes.len()));
+        }
+        let len = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as usize;
+        let frame = &bytes[4..];
+        if frame.len() != len {
+            return Err(format!(
+                "Message declares {} bytes but carries {}",
+                len,
+                frame.len()
+            ));
+        }
+        let body = &frame[1..];
+        match frame[0] {
+            TAG_PING => decode_u64(body, "Ping timestamp").map(NetMessage::Ping),
+            TAG_PONG => decode_u64(body, "Pong timestamp").map(NetMessage::Pong),
+            TAG_LOG => IslandReplicationLogEntry::decode(body).map(NetMessage::Log),
+            TAG_CHAT => String::from_utf8(body.to_vec())
+                .map(NetMessage::Chat)
+                .map_err(|e| format!("Chat message is not UTF-8: {}", e)),
+            TAG_LOG_CHUNK => LogChunk::decode(body).map(NetMessage::LogChunk),
+            TAG_RESEND => decode_u64(body, "Resend entry").map(NetMessage::Resend),
+            TAG_LOG_HEAD => decode_u64(body, "Log head entry").map(NetMessage::LogHead),
+            tag => Err(format!("Unknown message tag {}", tag)),
+        }
+    }
+}
+
+fn decode_u64(body: &[u8], what: &str) -> Result<u64, String> {
+    let value: [u8; 8] = body
+        .try_into()
+        .map_err(|_| format!("{} must be 8 bytes, got {}", what, body.len()))?;
+    Ok(u64::from_be_bytes(value))
+}
+
+/// Rolling average over the last `LATENCY_SAMPLES` ping round trips
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: VecDeque<Duration>,
+}
+
+impl LatencyTracker {
+    /// Add a round trip, dropping the oldest once full, and return the new average
+    pub fn record(&mut self, rtt: Duration) -> Duration {
+        if self.samples.len() == LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt);
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+}
+
+/// What the socket task should do with an inbound datagram, see `RecvRateLimiter::check`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Throttle {
+    /// Within the rate: handle it
+    Pass,
+    /// The first one over the rate: drop it and tell the player throttling started
+    Started,
+    /// Over the rate while already throttling: drop it quietly
+    Dropped,
+    /// Back within the rate: handle it and report how many were dropped meanwhile
+    Ended { dropped: u64 },
+}
+
+/// Token bucket guarding the receive loop, so a flooding peer can't swamp the event channel
+/// and the main thread with more datagrams than the game can process
+#[derive(Debug)]
+pub struct RecvRateLimiter {
+    rate_per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+    /// Datagrams dropped since throttling started, or None while not throttling
+    dropped: Option<u64>,
+}
+
+impl RecvRateLimiter {
+    /// A full bucket of `burst` tokens, refilled at `rate_per_sec`
+    pub fn new(rate_per_sec: u32, burst: u32, now: Instant) -> Self {
+        RecvRateLimiter {
+            rate_per_sec: rate_per_sec as f64,
+            burst: burst as f64,
+            tokens: burst as f64,
+            refilled_at: now,
+            dropped: None,
+        }
+    }
+
+    /// Spend a token on a datagram that arrived at `now`
+    pub fn check(&mut self, now: Instant) -> Throttle {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate_per_sec).min(self.burst);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return match self.dropped.take() {
+                Some(dropped) => Throttle::Ended { dropped },
+                None => Throttle::Pass,
+            };
+        }
+        match &mut self.dropped {
+            Some(dropped) => {
+                *dropped += 1;
+                Throttle::Dropped
+            }
+            None => {
+                self.dropped = Some(1);
+                Throttle::Started
+            }
+        }
+    }
+
+    /// Whether the bucket has refilled by `now` and isn't throttling, so forgetting it
+    /// changes nothing
+    fn is_idle(&self, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.dropped.is_none() && self.tokens + elapsed * self.rate_per_sec >= self.burst
+    }
+}
+
+/// A `RecvRateLimiter` per sender, so one flooding peer only throttles its own datagrams
+#[derive(Debug)]
+pub struct SenderRateLimiter {
+    rate_per_sec: u32,
+    burst: u32,
+    senders: HashMap<String, RecvRateLimiter>,
+    /// Shared by senders arriving while `senders` is full of active ones
+    overflow: RecvRateLimiter,
+}
+
+impl SenderRateLimiter {
+    /// Every sender starts with a full bucket of `burst` tokens, refilled at `rate_per_sec`
+    pub fn new(rate_per_sec: u32, burst: u32, now: Instant) -> Self {
+        SenderRateLimiter {
+            rate_per_sec,
+            burst,
+            senders: HashMap::new(),
+            overflow: RecvRateLimiter::new(rate_per_sec, burst, now),
+        }
+    }
+
+    /// Spend a token from `sender`'s bucket on a datagram that arrived at `now`
+    pub fn check(&mut self, sender: &str, now: Instant) -> Throttle {
+        if !self.senders.contains_key(sender) && self.senders.len() >= MAX_RATE_LIMITED_SENDERS {
+            self.senders.retain(|_, limiter| !limiter.is_idle(now));
+        }
+        if let Some(limiter) = self.senders.get_mut(sender) {
+            return limiter.check(now);
+        }
+        if self.senders.len() >= MAX_RATE_LIMITED_SENDERS {
+            return self.overflow.check(now);
+        }
+        self.senders
+            .entry(sender.to_string())
+            .or_insert_with(|| RecvRateLimiter::new(self.rate_per_sec, self.burst, now))
+            .check(now)
+    }
+}
+
+/// Log datagrams waiting to go out from the host, drained by `next_batch` every
+/// `LOG_SEND_INTERVAL` so replaying a long log can't trip the receivers' rate limiters
+#[derive(Debug)]
+pub struct LogOutbox<A> {
+    queue: VecDeque<(A, Vec<u8>)>,
+}
+
+impl<A> Default for LogOutbox<A> {
+    fn default() -> Self {
+        LogOutbox {
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<A: Clone> LogOutbox<A> {
+    /// Queue `datagrams` for `to`, after everything already queued
+    pub fn push(&mut self, to: &A, datagrams: Vec<Vec<u8>>) {
+        self.queue
+            .extend(datagrams.into_iter().map(|datagram| (to.clone(), datagram)));
+    }
+
+    /// Drop everything still queued for receivers matching `is_receiver`, before a
+    /// resend queues the entries again
+    pub fn cancel(&mut self, is_receiver: impl Fn(&A) -> bool) {
+        self.queue.retain(|(to, _)| !is_receiver(to));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Whether anything is still queued for a receiver matching `is_receiver`
+    pub fn is_sending_to(&self, is_receiver: impl Fn(&A) -> bool) -> bool {
+        self.queue.iter().any(|(to, _)| is_receiver(to))
+    }
+
+    /// The datagrams to send this `LOG_SEND_INTERVAL`, oldest first
+    pub fn next_batch(&mut self) -> Vec<(A, Vec<u8>)> {
+        let count = self.queue.len().min(LOG_DATAGRAMS_PER_SEND);
+        self.queue.drain(..count).collect()
+    }
+}
+
+/// Backoff before the 1-based reconnect `attempt`: 100ms, 200ms, 400ms, ... capped at 5s
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let millis = (RECONNECT_BASE_DELAY_MS << exponent).min(RECONNECT_MAX_DELAY_MS);
+    Duration::from_millis(millis)
+}
+
+/// Datagram and byte counters for one session, bumped by the socket task on every send and receive
+#[derive(Debug, Default)]
+pub struct SocketStats {
+    sent: AtomicU64,
+    recv: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+/// Point-in-time copy of `SocketStats`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SocketStatsSnapshot {
+    pub sent: u64,
+    pub recv: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+impl SocketStats {
+    pub fn record_sent(&self, bytes: usize) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_recv(&self, bytes: usize) {
+        self.recv.fetch_add(1, Ordering::Relaxed);
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> SocketStatsSnapshot {
+        SocketStatsSnapshot {
+            sent: self.sent.load(Ordering::Relaxed),
+            recv: self.recv.load(Ordering::Relaxed),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Public IP from a plain HTTP/1.0 response whose body is just the address, as icanhazip.com sends
+pub fn parse_public_ip_response(response: &[u8]) -> Result<IpAddr, String> {
+    let response = std::str::from_utf8(response)
+        .map_err(|e| format!("Public IP response is not UTF-8: {}", e))?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| "Public IP response has no body".to_string())?;
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Public IP lookup failed: {}", status_line));
+    }
+    let body = body.trim();
+    body.parse()
+        .map_err(|e| format!("Public IP response {:?} is not an address: {}", body, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry: u64, value: &[u8]) -> IslandReplicationLogEntry {
+        IslandReplicationLogEntry {
+            entry,
+            value: value.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_log_entry_round_trip() {
+        let original = entry(42, b"room tiles");
+        let decoded = IslandReplicationLogEntry::decode(&original.encode()).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_log_entry_decode_rejects_bad_lengths() {
+        assert!(IslandReplicationLogEntry::decode(&[0; 4]).is_err());
+
+        let mut bytes = entry(1, b"abc").encode();
+        bytes.pop();
+        let err = IslandReplicationLogEntry::decode(&bytes).unwrap_err();
+        assert!(err.contains("declares 3 payload bytes but carries 2"));
+    }
+
+    #[test]
+    fn test_receiver_buffers_until_gap_fills() {
+        let mut receiver = ReplicationLogReceiver::default();
+
+        assert!(receiver.receive(entry(1, b"b")).is_empty());
+        assert!(receiver.receive(entry(2, b"c")).is_empty());
+
+        let ready = receiver.receive(entry(0, b"a"));
+        let numbers: Vec<u64> = ready.iter().map(|e| e.entry).collect();
+        assert_eq!(numbers, vec![0, 1, 2]);
+        assert_eq!(ready[2].value, b"c");
+        assert_eq!(receiver.next_entry(), 3);
+    }
+
+    #[test]
+    fn test_receiver_drops_duplicates() {
+        let mut receiver = ReplicationLogReceiver::default();
+
+        assert_eq!(receiver.receive(entry(0, b"a")).len(), 1);
+        assert!(receiver.receive(entry(0, b"a")).is_empty());
+
+        assert!(receiver.receive(entry(2, b"c")).is_empty());
+        assert!(receiver.receive(entry(2, b"c")).is_empty());
+        assert_eq!(receiver.receive(entry(1, b"b")).len(), 2);
+    }
+
+    #[test]
+    fn test_receiver_drops_entries_past_reorder_window() {
+        let mut receiver = ReplicationLogReceiver::default();
+
+        assert!(
+            receiver
+                .receive(entry(LOG_REORDER_WINDOW, b"far"))
+                .is_empty()
+        );
+        assert!(receiver.receive(entry(u64::MAX, b"forged")).is_empty());
+        assert!(receiver.resend_from(Instant::now()).is_none());
+        assert!(receiver.receive(entry(1, b"b")).is_empty());
+
+        let ready = receiver.receive(entry(0, b"a"));
+        let numbers: Vec<u64> = ready.iter().map(|e| e.entry).collect();
+        assert_eq!(numbers, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_receiver_asks_for_resend_when_a_gap_stalls() {
+        let start = Instant::now();
+        let mut receiver = ReplicationLogReceiver::default();
+        assert_eq!(receiver.resend_from(start), None);
+
+        assert!(receiver.receive(entry(1, b"b")).is_empty());
+        assert_eq!(receiver.resend_from(start), None);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT), Some(0));
+        // Give the resend time to arrive before asking again
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT), None);
+
+        assert_eq!(receiver.receive(entry(0, b"a")).len(), 2);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT * 3), None);
+    }
+
+    #[test]
+    fn test_receiver_asks_for_resend_when_behind_the_host() {
+        let start = Instant::now();
+        let mut receiver = ReplicationLogReceiver::default();
+        assert_eq!(receiver.receive(entry(0, b"a")).len(), 1);
+
+        // Entries 1 and 2 were lost with nothing after them to reveal the gap
+        receiver.note_host_next(3);
+        assert_eq!(receiver.resend_from(start), None);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT), Some(1));
+
+        // A late, stale head doesn't move the host's advertised end back
+        receiver.note_host_next(1);
+        assert_eq!(receiver.receive(entry(1, b"b")).len(), 1);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT * 2), None);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT * 3), Some(2));
+
+        assert_eq!(receiver.receive(entry(2, b"c")).len(), 1);
+        assert_eq!(receiver.resend_from(start + LOG_GAP_TIMEOUT * 5), None);
+    }
+
+    #[test]
+    fn test_net_message_round_trip() {
+        let messages = vec![
+            NetMessage::Ping(0),
+            NetMessage::Pong(u64::MAX),
+            NetMessage::Log(entry(7, b"\x00\x01\x02")),
+            NetMessage::Chat("ahoy".to_string()),
+            NetMessage::Resend(42),
+            NetMessage::LogHead(9),
+            NetMessage::LogChunk(LogChunk {
+                entry: 3,
+                index: 1,
+                total: 2,
+                fragment: b"tail".to_vec(),
+            }),
+        ];
+        for message in messages {
+            let decoded = NetMessage::decode(&message.encode()).unwrap();
+            assert_eq!(decoded, message);
+        }
+    }
+
+    #[test]
+    fn test_net_message_frame_layout() {
+        assert_eq!(
+            NetMessage::Ping(258).encode(),
+            vec![0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 1, 2]
+        );
+        assert_eq!(
+            NetMessage::Chat("hi".to_string()).encode(),
+            vec![0, 0, 0, 3, 3, b'h', b'i']
+        );
+    }
+
+    #[test]
+    fn test_net_message_decode_rejects_malformed() {
+        assert!(NetMessage::decode(&[0, 0, 0]).is_err());
+        assert!(NetMessage::decode(&[0, 0, 0, 2, 0]).is_err());
+
+        let err = NetMessage::decode(&[0, 0, 0, 1, 9]).unwrap_err();
+        assert_eq!(err, "Unknown message tag 9");
+
+        let err = NetMessage::decode(&[0, 0, 0, 2, 3, 0xff]).unwrap_err();
+        assert!(err.contains("not UTF-8"));
+
+        let err = NetMessage::decode(&[0, 0, 0, 1, 1]).unwrap_err();
+        assert_eq!(err, "Pong timestamp must be 8 bytes, got 0");
+    }
+
+    #[test]
+    fn test_latency_tracker_averages_recent_samples() {
+        let mut tracker = LatencyTracker::default();
+        assert_eq!(
+            tracker.record(Duration::from_millis(10)),
+            Duration::from_millis(10)
+        );
+        assert_eq!(
+            tracker.record(Duration::from_millis(30)),
+            Duration::from_millis(20)
+        );
+
+        for _ in 0..LATENCY_SAMPLES {
+            tracker.record(Duration::from_millis(50));
+        }
+        // The early fast samples have rolled out of the window
+        assert_eq!(
+            tracker.record(Duration::from_millis(50)),
+            Duration::from_millis(50)
+        );
+    }
+
+    #[test]
+    fn test_recv_rate_limiter_drops_past_the_burst() {
+        let start = Instant::now();
+        let mut limiter = RecvRateLimiter::new(10, 3, start);
+        for _ in 0..3 {
+            assert_eq!(limiter.check(start), Throttle::Pass);
+        }
+        assert_eq!(limiter.check(start), Throttle::Started);
+        assert_eq!(limiter.check(start), Throttle::Dropped);
+        assert_eq!(limiter.check(start), Throttle::Dropped);
+
+        // One token refills every 100ms at 10 per second
+        let later = start + Duration::from_millis(100);
+        assert_eq!(limiter.check(later), Throttle::Ended { dropped: 3 });
+        assert_eq!(limiter.check(later), Throttle::Started);
+
+        // A long quiet spell refills up to the burst, no further
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(limiter.check(much_later), Throttle::Ended { dropped: 1 });
+        assert_eq!(limiter.check(much_later), Throttle::Pass);
+        assert_eq!(limiter.check(much_later), Throttle::Pass);
+        assert_eq!(limiter.check(much_later), Throttle::Started);
+    }
+
+    #[test]
+    fn test_flooding_sender_only_throttles_itself() {
+        let start = Instant::now();
+        let mut limiter = SenderRateLimiter::new(10, 3, start);
+        for _ in 0..3 {
+            assert_eq!(limiter.check("flooder", start), Throttle::Pass);
+        }
+        assert_eq!(limiter.check("flooder", start), Throttle::Started);
+        assert_eq!(limiter.check("flooder", start), Throttle::Dropped);
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check("host", start), Throttle::Pass);
+        }
+    }
+
+    #[test]
+    fn test_paced_replay_of_the_largest_entry_passes_the_rate_limiter() {
+        let value: Vec<u8> = (0..MAX_LOG_ENTRY_LEN).map(|i| (i % 251) as u8).collect();
+        let log = vec![entry(0, b"a"), entry(1, &value), entry(2, b"c")];
+        let mut outbox = LogOutbox::default();
+        for logged in &log {
+            outbox.push(&"client", log_entry_datagrams(logged).unwrap());
+        }
+
+        let mut now = Instant::now();
+        let mut limiter = SenderRateLimiter::new(RECV_RATE_PER_SEC, RECV_BURST, now);
+        let mut reassembler = ChunkReassembler::default();
+        let mut receiver = ReplicationLogReceiver::default();
+        let mut applied = Vec::new();
+        while !outbox.is_empty() {
+            for (to, datagram) in outbox.next_batch() {
+                assert_eq!(to, "client");
+                assert_eq!(limiter.check("host", now), Throttle::Pass);
+                let complete = match NetMessage::decode(&datagram) {
+                    Ok(NetMessage::Log(entry)) => Some(entry),
+                    Ok(NetMessage::LogChunk(chunk)) => reassembler.accept(chunk, now),
+                    other => panic!("expected a log message, got {:?}", other),
+                };
+                if let Some(complete) = complete {
+                    applied.extend(receiver.receive(complete));
+                }
+            }
+            now += LOG_SEND_INTERVAL;
+        }
+        assert_eq!(applied, log);
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_log_outbox_cancel_drops_only_that_receiver() {
+        let mut outbox = LogOutbox::default();
+        outbox.push(&"a", vec![vec![1], vec![2]]);
+        outbox.push(&"b", vec![vec![3]]);
+        outbox.cancel(|to| *to == "a");
+        assert_eq!(outbox.next_batch(), vec![("b", vec![3])]);
+        assert!(outbox.is_empty());
+    }
+
+    #[test]
+    fn test_socket_stats_counts_datagrams_and_bytes() {
+        let stats = SocketStats::default();
+        stats.record_sent(5);
+        stats.record_sent(12);
+        stats.record_recv(7);
+
+        assert_eq!(
+            stats.snapshot(),
+            SocketStatsSnapshot {
+                sent: 2,
+                recv: 1,
+                bytes_in: 7,
+                bytes_out: 17,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_public_ip_response() {
+        let response = b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\n203.0.113.7\n";
+        assert_eq!(
+            parse_public_ip_response(response),
+            Ok("203.0.113.7".parse().unwrap())
+        );
+
+        let response = b"HTTP/1.0 200 OK\r\n\r\n2001:db8::1\n";
+        assert_eq!(
+            parse_public_ip_response(response),
+            Ok("2001:db8::1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_public_ip_response_rejects_failures() {
+        let err =
+            parse_public_ip_response(b"HTTP/1.0 503 Service Unavailable\r\n\r\n").unwrap_err();
+        assert_eq!(
+            err,
+            "Public IP lookup failed: HTTP/1.0 503 Service Unavailable"
+        );
+
+        assert!(parse_public_ip_response(b"HTTP/1.0 200 OK\r\n").is_err());
+        assert!(parse_public_ip_response(b"HTTP/1.0 200 OK\r\n\r\n<html>").is_err());
+    }
+
+    #[test]
+    fn test_small_log_entries_are_not_chunked() {
+        let small = entry(4, b"door opened");
+        let datagrams = log_entry_datagrams(&small).unwrap();
+        assert_eq!(datagrams, vec![NetMessage::Log(small).encode()]);
+    }
+
+    #[test]
+    fn test_oversized_log_entries_are_rejected() {
+        let oversized = IslandReplicationLogEntry {
+            entry: 3,
+            value: vec![0; MAX_LOG_ENTRY_LEN + 1],
+        };
+        let err = log_entry_datagrams(&oversized).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "Log entry 3 is {} bytes, over the {} byte limit",
+                MAX_LOG_ENTRY_LEN + 1,
+                MAX_LOG_ENTRY_LEN
+            )
+        );
+    }
+
+    #[test]
+    fn test_large_log_entries_round_trip_through_chunks() {
+        let value: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+        let large = entry(9, &value);
+        let mut datagrams = log_entry_datagrams(&large).unwrap();
+        assert_eq!(datagrams.len(), 5);
+        assert!(datagrams.iter().all(|d| d.len() <= MAX_DATAGRAM_LEN));
+
+        // Out of order, as datagrams may arrive
+        datagrams.reverse();
+        let now = Instant::now();
+        let mut reassembler = ChunkReassembler::default();
+        let mut completed = Vec::new();
+        for datagram in datagrams {
+            let Ok(NetMessage::LogChunk(chunk)) = NetMessage::decode(&datagram) else {
+                panic!("expected a log chunk");
+            };
+            completed.extend(reassembler.accept(chunk, now));
+        }
+        assert_eq!(completed, vec![large]);
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_reassembly_drops_incomplete_entries_after_timeout() {
+        let chunk = |entry: u64, index: u16| LogChunk {
+            entry,
+            index,
+            total: 2,
+            fragment: vec![index as u8],
+        };
+        let start = Instant::now();
+        let mut reassembler = ChunkReassembler::default();
+        assert_eq!(reassembler.accept(chunk(1, 0), start), None);
+        assert_eq!(reassembler.pending(), 1);
+
+        // The other half of entry 1 arrives too late to complete it
+        let late = start + CHUNK_REASSEMBLY_TIMEOUT;
+        assert_eq!(reassembler.accept(chunk(2, 0), late), None);
+        assert_eq!(reassembler.accept(chunk(1, 1), late), None);
+        assert_eq!(reassembler.pending(), 2);
+
+        assert_eq!(
+            reassembler.accept(chunk(2, 1), late),
+            Some(entry(2, &[0, 1]))
+        );
+    }
+
+    #[test]
+    fn test_log_chunk_decode_rejects_index_past_total() {
+        let bad = LogChunk {
+            entry: 1,
+            index: 2,
+            total: 2,
+            fragment: Vec::new(),
+        };
+        let err = LogChunk::decode(&bad.encode()).unwrap_err();
+        assert_eq!(err, "Log chunk 2 of entry 1 is outside its count of 2");
+    }
+
+    #[test]
+    fn test_reconnect_delay_doubles_then_caps() {
+        assert_eq!(reconnect_delay(1), Duration::from_millis(100));
+        assert_eq!(reconnect_delay(2), Duration::from_millis(200));
+        assert_eq!(reconnect_delay(3), Duration::from_millis(400));
+        assert_eq!(reconnect_delay(7), Duration::from_millis(5_000));
+        assert_eq!(reconnect_delay(u32::MAX), Duration::from_millis(5_000));
+    }
+}